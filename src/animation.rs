@@ -0,0 +1,460 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use serde::Deserialize;
+
+use crate::{
+    backend::{Backend, Monitor, MonitorId, Rectangle, WindowId},
+    hook::{WindowEvent, WindowEventKind},
+};
+
+/// Time between two frames of an animation
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Source of the current time, replaced by a manual clock in tests
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock only moving forward when told to
+#[cfg(test)]
+pub struct ManualClock(pub std::cell::Cell<Instant>);
+
+#[cfg(test)]
+impl ManualClock {
+    pub fn advance(&self, duration: Duration) {
+        self.0.set(self.0.get() + duration);
+    }
+}
+
+#[cfg(test)]
+impl Clock for &ManualClock {
+    fn now(&self) -> Instant {
+        self.0.get()
+    }
+}
+
+/// How the progress of an animation maps to the distance covered
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    Linear,
+    /// Starts fast and slows down before reaching the target
+    #[default]
+    EaseOutCubic,
+    EaseInOutCubic,
+}
+
+impl Easing {
+    /// Maps a progress in [0, 1] to a proportion of the distance in [0, 1]
+    pub fn apply(self, progress: f32) -> f32 {
+        match self {
+            Self::Linear => progress,
+            Self::EaseOutCubic => 1.0 - (1.0 - progress).powi(3),
+            Self::EaseInOutCubic if progress < 0.5 => 4.0 * progress.powi(3),
+            Self::EaseInOutCubic => 1.0 - (-2.0 * progress + 2.0).powi(3) / 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Animation {
+    from: Rectangle,
+    to: Rectangle,
+    start: Instant,
+}
+
+impl Animation {
+    /// Returns the proportion of the animation elapsed at `now`, capped to 1
+    fn progress(&self, now: Instant, duration: Duration) -> f32 {
+        (now.duration_since(self.start).as_secs_f32() / duration.as_secs_f32()).min(1.0)
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        reason = "screen coordinates are small enough to be represented exactly by a f32"
+    )]
+    fn rect_at(&self, distance: f32) -> Rectangle {
+        let interpolate =
+            |from: i32, to: i32| (from as f32 + (to - from) as f32 * distance).round() as i32;
+        Rectangle {
+            x: interpolate(self.from.x, self.to.x),
+            y: interpolate(self.from.y, self.to.y),
+            width: interpolate(self.from.width, self.to.width),
+            height: interpolate(self.from.height, self.to.height),
+        }
+    }
+}
+
+/// Backend moving windows to their new rectangle over `duration` instead of in one step.
+///
+/// Scrolling moves every window of the strip, so the viewport is animated through them. Moving
+/// a window again while it is animated starts the new animation from where it currently is.
+///
+/// Windows are only moved when their target changes or something else moved them, so that
+/// laying the strip out again only costs system calls for the windows that actually move. Their
+/// rectangle in the system does not match the requested one exactly, so where they ended up is
+/// compared instead.
+pub struct Animated<B, C> {
+    backend: B,
+    clock: C,
+    duration: Duration,
+    easing: Easing,
+    animations: RefCell<HashMap<WindowId, Animation>>,
+    /// Last rectangle requested for every window, forgotten once something else may move it
+    targets: RefCell<HashMap<WindowId, Target>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Target {
+    requested: Rectangle,
+    /// Rectangle the system reported once the window got there, unknown while it is animated
+    placed: Option<Rectangle>,
+}
+
+impl<B: Backend, C: Clock> Animated<B, C> {
+    /// A zero `duration` moves the windows right away
+    pub fn new(backend: B, clock: C, duration: Duration, easing: Easing) -> Self {
+        Self {
+            backend,
+            clock,
+            duration,
+            easing,
+            animations: RefCell::default(),
+            targets: RefCell::default(),
+        }
+    }
+
+    /// Stops animating the window and forgets where it was sent, the next move placing it again
+    fn forget(&self, window: WindowId) {
+        self.animations.borrow_mut().remove(&window);
+        self.targets.borrow_mut().remove(&window);
+    }
+
+    /// Remembers where the system put the window that reached its target
+    fn placed(&self, window: WindowId) {
+        let placed = self.backend.window_rect(window).ok();
+        if let Some(target) = self.targets.borrow_mut().get_mut(&window) {
+            target.placed = placed;
+        }
+    }
+
+    /// Returns whether the window is on its way to `rect`, or still where it was put there
+    fn is_at(&self, window: WindowId, rect: Rectangle) -> bool {
+        let Some(target) = self.targets.borrow().get(&window).copied() else {
+            return false;
+        };
+        target.requested == rect
+            && (self.animations.borrow().contains_key(&window)
+                || target.placed.is_some_and(|placed| {
+                    self.backend
+                        .window_rect(window)
+                        .is_ok_and(|current| current == placed)
+                }))
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.animations.borrow().is_empty()
+    }
+
+    /// Moves the animated windows to their position at the current time, returning whether some
+    /// are still moving
+    pub fn tick(&self) -> bool {
+        let now = self.clock.now();
+        self.animations.borrow_mut().retain(|window, animation| {
+            let progress = animation.progress(now, self.duration);
+            let rect = animation.rect_at(self.easing.apply(progress));
+            if let Err(err) = self.backend.move_window(*window, rect) {
+                warn!("Failed to move window {window:?}: {err}");
+                self.targets.borrow_mut().remove(window);
+                return false;
+            }
+            if progress >= 1.0 {
+                self.placed(*window);
+            }
+            progress < 1.0
+        });
+        self.is_animating()
+    }
+
+    /// Moves the animated windows to their target right away
+    pub fn finish(&self) {
+        for (window, animation) in self.animations.borrow_mut().drain() {
+            if let Err(err) = self.backend.move_window(window, animation.to) {
+                warn!("Failed to move window {window:?}: {err}");
+            }
+        }
+    }
+}
+
+impl<B: Backend, C: Clock> Backend for Animated<B, C> {
+    fn opened_windows(&self) -> anyhow::Result<Vec<WindowId>> {
+        self.backend.opened_windows()
+    }
+
+    fn monitors(&self) -> anyhow::Result<Vec<Monitor>> {
+        self.backend.monitors()
+    }
+
+    fn monitor_of(&self, window: WindowId) -> anyhow::Result<MonitorId> {
+        self.backend.monitor_of(window)
+    }
+
+    fn focused_window(&self) -> Option<WindowId> {
+        self.backend.focused_window()
+    }
+
+    fn focus(&self, window: WindowId) -> anyhow::Result<()> {
+        self.backend.focus(window)
+    }
+
+    fn window_rect(&self, window: WindowId) -> anyhow::Result<Rectangle> {
+        self.backend.window_rect(window)
+    }
+
+    fn move_window(&self, window: WindowId, rect: Rectangle) -> anyhow::Result<()> {
+        // Laying the strip out again leaves the windows that already go to the same place alone
+        if self.is_at(window, rect) {
+            return Ok(());
+        }
+        let previous_target = self
+            .targets
+            .borrow_mut()
+            .insert(
+                window,
+                Target {
+                    requested: rect,
+                    placed: None,
+                },
+            )
+            .map(|target| target.requested);
+        let move_now = || {
+            self.backend
+                .move_window(window, rect)
+                .inspect_err(|_| self.forget(window))?;
+            self.placed(window);
+            Ok(())
+        };
+        if self.duration.is_zero() {
+            return move_now();
+        }
+        let now = self.clock.now();
+        let current = self.animations.borrow().get(&window).map(|animation| {
+            animation.rect_at(self.easing.apply(animation.progress(now, self.duration)))
+        });
+        let from = match (current, previous_target) {
+            (Some(current), _) => current,
+            (None, Some(previous_target)) if previous_target != rect => previous_target,
+            // Windows placed for the first time start from where they opened, and the ones moved
+            // by something else from where it left them
+            (None, _) => match self.backend.window_rect(window) {
+                Ok(from) if from != rect => from,
+                _ => return move_now(),
+            },
+        };
+        self.animations.borrow_mut().insert(
+            window,
+            Animation {
+                from,
+                to: rect,
+                start: now,
+            },
+        );
+        Ok(())
+    }
+
+    fn set_visible(&self, window: WindowId, visible: bool) -> anyhow::Result<()> {
+        // Moving a window shows it, the animation must not bring hidden windows back
+        if !visible {
+            self.forget(window);
+        }
+        self.backend.set_visible(window, visible)
+    }
+
+    fn is_valid(&self, window: WindowId) -> bool {
+        self.backend.is_valid(window)
+    }
+
+    fn title(&self, window: WindowId) -> anyhow::Result<Option<String>> {
+        self.backend.title(window)
+    }
+
+    fn class(&self, window: WindowId) -> anyhow::Result<String> {
+        self.backend.class(window)
+    }
+
+    fn process_name(&self, window: WindowId) -> anyhow::Result<String> {
+        self.backend.process_name(window)
+    }
+
+    fn window_changed(&self, event: &WindowEvent) {
+        // The window was moved by something else than winri, or is not the same window anymore
+        if matches!(
+            event.kind,
+            WindowEventKind::Created
+                | WindowEventKind::Destroyed
+                | WindowEventKind::Shown
+                | WindowEventKind::Restored
+                | WindowEventKind::MoveSizeStarted
+                | WindowEventKind::MoveSizeEnded
+        ) {
+            self.forget(event.window);
+        }
+        self.backend.window_changed(event);
+    }
+
+    fn forget_focus(&self) {
+        self.backend.forget_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::backend::fake::FakeBackend;
+
+    fn rect(x: i32, width: i32) -> Rectangle {
+        Rectangle {
+            x,
+            y: 0,
+            width,
+            height: 100,
+        }
+    }
+
+    #[test]
+    fn windows_move_along_the_easing_curve() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let backend = Animated::new(
+            FakeBackend::new(),
+            &clock,
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        let window = backend.backend.open("window");
+        backend.backend.move_window(window, rect(0, 100)).unwrap();
+
+        backend.move_window(window, rect(200, 300)).unwrap();
+        assert_eq!(backend.backend.rect(window), rect(0, 100));
+
+        clock.advance(Duration::from_millis(25));
+        assert!(backend.tick());
+        assert_eq!(backend.backend.rect(window), rect(50, 150));
+
+        clock.advance(Duration::from_millis(100));
+        assert!(!backend.tick());
+        assert_eq!(backend.backend.rect(window), rect(200, 300));
+    }
+
+    #[test]
+    fn interrupted_animations_start_from_the_current_position() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let backend = Animated::new(
+            FakeBackend::new(),
+            &clock,
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        let window = backend.backend.open("window");
+        backend.backend.move_window(window, rect(0, 100)).unwrap();
+        backend.move_window(window, rect(200, 100)).unwrap();
+        clock.advance(Duration::from_millis(50));
+        backend.tick();
+
+        // The window turns back from the middle of its way
+        backend.move_window(window, rect(0, 100)).unwrap();
+        clock.advance(Duration::from_millis(50));
+        backend.tick();
+        assert_eq!(backend.backend.rect(window), rect(50, 100));
+    }
+
+    #[test]
+    fn hidden_windows_stop_moving() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let backend = Animated::new(
+            FakeBackend::new(),
+            &clock,
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        let window = backend.backend.open("window");
+        backend.backend.move_window(window, rect(0, 100)).unwrap();
+        backend.move_window(window, rect(200, 100)).unwrap();
+        clock.advance(Duration::from_millis(50));
+        backend.tick();
+
+        // Switching workspaces in the middle of a scroll
+        backend.set_visible(window, false).unwrap();
+        clock.advance(Duration::from_millis(16));
+        assert!(!backend.tick());
+        assert!(!backend.backend.is_visible(window));
+    }
+
+    #[test]
+    fn windows_staying_in_place_are_not_animated() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let backend = Animated::new(
+            FakeBackend::new(),
+            &clock,
+            Duration::from_millis(100),
+            Easing::Linear,
+        );
+        let window = backend.backend.open("window");
+        backend.move_window(window, rect(200, 100)).unwrap();
+        clock.advance(Duration::from_millis(100));
+        assert!(!backend.tick());
+
+        backend.move_window(window, rect(200, 100)).unwrap();
+        assert!(!backend.is_animating());
+    }
+
+    #[test]
+    fn windows_are_only_moved_when_their_target_changes() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let backend = Animated::new(FakeBackend::new(), &clock, Duration::ZERO, Easing::Linear);
+        let window = backend.backend.open("window");
+        backend.move_window(window, rect(0, 100)).unwrap();
+
+        // Sending the window to the same place again does not touch it
+        backend.move_window(window, rect(0, 100)).unwrap();
+        assert_eq!(backend.backend.moves.get(), 1);
+
+        // Unless something else moved it, such as the application restoring its saved bounds
+        backend.backend.move_window(window, rect(50, 100)).unwrap();
+        backend.move_window(window, rect(0, 100)).unwrap();
+        assert_eq!(backend.backend.rect(window), rect(0, 100));
+        assert_eq!(backend.backend.moves.get(), 3);
+
+        // Or the user dragged it, even back to the same place
+        backend.window_changed(&WindowEvent {
+            kind: WindowEventKind::MoveSizeEnded,
+            window,
+        });
+        backend.move_window(window, rect(0, 100)).unwrap();
+        assert_eq!(backend.backend.moves.get(), 4);
+    }
+
+    #[test]
+    fn easing_curves_start_at_zero_and_end_at_one() {
+        for easing in [Easing::Linear, Easing::EaseOutCubic, Easing::EaseInOutCubic] {
+            assert!(easing.apply(0.0).abs() < f32::EPSILON);
+            assert!((easing.apply(1.0) - 1.0).abs() < f32::EPSILON);
+        }
+        assert!(Easing::EaseOutCubic.apply(0.5) > 0.5);
+    }
+}
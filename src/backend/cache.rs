@@ -0,0 +1,237 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+use crate::{
+    backend::WindowId,
+    hook::{WindowEvent, WindowEventKind},
+};
+
+/// Value read from the system, or not read yet
+#[derive(Debug, Default, Clone, Copy)]
+enum Cached<T> {
+    #[default]
+    Unknown,
+    Known(T),
+}
+
+/// Properties of a window read so far, each being read at most once until an event changes it
+#[derive(Debug, Default)]
+struct CachedProperties {
+    process_name: Cached<String>,
+    class: Cached<String>,
+    title: Cached<Option<String>>,
+    style: Cached<u32>,
+}
+
+/// Properties of the windows kept between layout passes instead of being read from the system
+/// every time, dropped by the window events that may change them.
+///
+/// The focused window is only kept for the current pass, as the focus moves without events
+/// reaching the event loop first.
+#[derive(Debug, Default)]
+pub struct PropertyCache {
+    windows: RefCell<HashMap<WindowId, CachedProperties>>,
+    focused: Cell<Cached<Option<WindowId>>>,
+}
+
+impl PropertyCache {
+    /// Returns the cached property, calling `read` to fill it in if it is not known yet
+    fn get<T: Clone>(
+        &self,
+        window: WindowId,
+        field: fn(&mut CachedProperties) -> &mut Cached<T>,
+        read: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        if let Some(cached) = self.windows.borrow_mut().get_mut(&window)
+            && let Cached::Known(value) = field(cached)
+        {
+            return Ok(value.clone());
+        }
+        // The cache is not borrowed while reading, `read` may go through it as well
+        let value = read()?;
+        *field(self.windows.borrow_mut().entry(window).or_default()) = Cached::Known(value.clone());
+        Ok(value)
+    }
+
+    pub fn process_name(
+        &self,
+        window: WindowId,
+        read: impl FnOnce() -> anyhow::Result<String>,
+    ) -> anyhow::Result<String> {
+        self.get(window, |cached| &mut cached.process_name, read)
+    }
+
+    pub fn class(
+        &self,
+        window: WindowId,
+        read: impl FnOnce() -> anyhow::Result<String>,
+    ) -> anyhow::Result<String> {
+        self.get(window, |cached| &mut cached.class, read)
+    }
+
+    pub fn title(
+        &self,
+        window: WindowId,
+        read: impl FnOnce() -> anyhow::Result<Option<String>>,
+    ) -> anyhow::Result<Option<String>> {
+        self.get(window, |cached| &mut cached.title, read)
+    }
+
+    pub fn style(
+        &self,
+        window: WindowId,
+        read: impl FnOnce() -> anyhow::Result<u32>,
+    ) -> anyhow::Result<u32> {
+        self.get(window, |cached| &mut cached.style, read)
+    }
+
+    /// Returns the focused window, only calling `read` once per layout pass
+    pub fn focused_window(&self, read: impl FnOnce() -> Option<WindowId>) -> Option<WindowId> {
+        if let Cached::Known(focused) = self.focused.get() {
+            return focused;
+        }
+        let focused = read();
+        self.focused.set(Cached::Known(focused));
+        focused
+    }
+
+    /// Forgets the focused window, at the start of a layout pass or once the focus moved
+    pub fn forget_focus(&self) {
+        self.focused.set(Cached::Unknown);
+    }
+
+    /// Forgets the windows that do not exist anymore, whose destruction may not have been reported
+    pub fn retain(&self, existing_windows: &HashSet<WindowId>) {
+        self.windows
+            .borrow_mut()
+            .retain(|window, _| existing_windows.contains(window));
+    }
+
+    /// Forgets the properties of the window that `event` may have changed
+    pub fn window_changed(&self, event: &WindowEvent) {
+        let mut windows = self.windows.borrow_mut();
+        match event.kind {
+            // Handles of destroyed windows are given to new ones
+            WindowEventKind::Created | WindowEventKind::Destroyed => {
+                windows.remove(&event.window);
+            }
+            WindowEventKind::TitleChanged => {
+                if let Some(cached) = windows.get_mut(&event.window) {
+                    cached.title = Cached::Unknown;
+                }
+            }
+            WindowEventKind::Shown
+            | WindowEventKind::Hidden
+            | WindowEventKind::Minimized
+            | WindowEventKind::Restored => {
+                if let Some(cached) = windows.get_mut(&event.window) {
+                    cached.style = Cached::Unknown;
+                }
+            }
+            WindowEventKind::Focused
+            | WindowEventKind::MoveSizeStarted
+            | WindowEventKind::MoveSizeEnded => {}
+        }
+        drop(windows);
+        if event.kind == WindowEventKind::Focused {
+            self.forget_focus();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_are_read_again_once_changed() {
+        let cache = PropertyCache::default();
+        let window = WindowId(1);
+        let reads = Cell::new(0);
+        let read_title = |title: &str| {
+            reads.set(reads.get() + 1);
+            Ok(Some(title.to_string()))
+        };
+
+        assert_eq!(
+            cache
+                .title(window, || read_title("first"))
+                .unwrap()
+                .as_deref(),
+            Some("first")
+        );
+        assert_eq!(
+            cache
+                .title(window, || read_title("second"))
+                .unwrap()
+                .as_deref(),
+            Some("first")
+        );
+        assert_eq!(
+            cache.class(window, || Ok("Class".to_string())).unwrap(),
+            "Class"
+        );
+        assert_eq!(reads.get(), 1);
+
+        cache.window_changed(&WindowEvent {
+            kind: WindowEventKind::TitleChanged,
+            window,
+        });
+        assert_eq!(
+            cache
+                .title(window, || read_title("second"))
+                .unwrap()
+                .as_deref(),
+            Some("second")
+        );
+        assert_eq!(cache.class(window, || unreachable!()).unwrap(), "Class");
+        assert_eq!(reads.get(), 2);
+
+        // Failed reads are not cached
+        assert!(
+            cache
+                .process_name(window, || anyhow::bail!("denied"))
+                .is_err()
+        );
+        assert_eq!(
+            cache
+                .process_name(window, || Ok("app.exe".to_string()))
+                .unwrap(),
+            "app.exe"
+        );
+    }
+
+    #[test]
+    fn the_focused_window_is_read_once_per_pass() {
+        let cache = PropertyCache::default();
+        assert_eq!(
+            cache.focused_window(|| Some(WindowId(1))),
+            Some(WindowId(1))
+        );
+        assert_eq!(
+            cache.focused_window(|| Some(WindowId(2))),
+            Some(WindowId(1))
+        );
+
+        cache.forget_focus();
+        assert_eq!(cache.focused_window(|| None), None);
+        assert_eq!(cache.focused_window(|| Some(WindowId(2))), None);
+    }
+
+    #[test]
+    fn windows_gone_from_the_snapshot_are_forgotten() {
+        let cache = PropertyCache::default();
+        for window in [WindowId(1), WindowId(2)] {
+            cache.class(window, || Ok("Class".to_string())).unwrap();
+        }
+
+        cache.retain(&HashSet::from([WindowId(2)]));
+        assert!(cache.class(WindowId(1), || anyhow::bail!("read")).is_err());
+        assert_eq!(
+            cache.class(WindowId(2), || unreachable!()).unwrap(),
+            "Class"
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use anyhow::Context;
+
+use crate::{
+    backend::{Backend, DEFAULT_DPI, Monitor, MonitorId, Rectangle, WindowId},
+    desktop::Desktop,
+    tiler::{Gaps, InsertPosition, Placement, ScrollPolicy, ScrollTiler},
+    workspace::Workspaces,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct FakeWindow {
+    pub title: Option<String>,
+    pub class: String,
+    pub process_name: String,
+    pub rect: Rectangle,
+    pub visible: bool,
+    pub monitor: usize,
+}
+
+/// In-memory backend, windows only exist inside of it
+pub struct FakeBackend {
+    pub monitors: Vec<Monitor>,
+    pub windows: RefCell<HashMap<WindowId, FakeWindow>>,
+    /// Windows from the top to the bottom of the z-order
+    pub z_order: RefCell<Vec<WindowId>>,
+    pub focused: Cell<Option<WindowId>>,
+    /// Number of `move_window` calls, which layouts leaving the windows in place do not make
+    pub moves: Cell<usize>,
+    next_id: Cell<isize>,
+}
+
+impl FakeBackend {
+    /// Creates a backend with a single 1000x500 monitor
+    pub fn new() -> Self {
+        Self::with_monitors(&[Rectangle {
+            x: 0,
+            y: 0,
+            width: 1000,
+            height: 500,
+        }])
+    }
+
+    pub fn with_monitors(rects: &[Rectangle]) -> Self {
+        Self {
+            monitors: rects
+                .iter()
+                .zip(0..)
+                .map(|(rect, id)| Monitor {
+                    id: MonitorId(id),
+                    rect: *rect,
+                    work_area: *rect,
+                    dpi: DEFAULT_DPI,
+                })
+                .collect(),
+            windows: RefCell::default(),
+            z_order: RefCell::default(),
+            focused: Cell::new(None),
+            moves: Cell::new(0),
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Opens a visible window on the first monitor and focuses it
+    pub fn open(&self, process_name: &str) -> WindowId {
+        self.open_on(process_name, 0)
+    }
+
+    pub fn open_on(&self, process_name: &str, monitor: usize) -> WindowId {
+        let id = WindowId(self.next_id.get());
+        self.next_id.set(id.0 + 1);
+        self.windows.borrow_mut().insert(
+            id,
+            FakeWindow {
+                title: Some(process_name.to_string()),
+                class: "FakeWindow".to_string(),
+                process_name: process_name.to_string(),
+                visible: true,
+                monitor,
+                ..Default::default()
+            },
+        );
+        self.raise(id);
+        id
+    }
+
+    /// Focuses the window and brings it to the top of the z-order
+    fn raise(&self, window: WindowId) {
+        let mut z_order = self.z_order.borrow_mut();
+        z_order.retain(|other| *other != window);
+        z_order.insert(0, window);
+        self.focused.set(Some(window));
+    }
+
+    pub fn close(&self, window: WindowId) {
+        self.windows.borrow_mut().remove(&window);
+        self.z_order.borrow_mut().retain(|other| *other != window);
+        if self.focused.get() == Some(window) {
+            self.focused.set(None);
+        }
+    }
+
+    pub fn rect(&self, window: WindowId) -> Rectangle {
+        self.windows.borrow()[&window].rect
+    }
+
+    pub fn set_title(&self, window: WindowId, title: &str) {
+        self.with_window(window, |window| window.title = Some(title.to_string()))
+            .unwrap();
+    }
+
+    pub fn is_visible(&self, window: WindowId) -> bool {
+        self.windows.borrow()[&window].visible
+    }
+
+    fn with_window<T>(
+        &self,
+        window: WindowId,
+        f: impl FnOnce(&mut FakeWindow) -> T,
+    ) -> anyhow::Result<T> {
+        self.windows
+            .borrow_mut()
+            .get_mut(&window)
+            .map(f)
+            .with_context(|| format!("Unknown window {window:?}"))
+    }
+}
+
+impl Backend for FakeBackend {
+    fn opened_windows(&self) -> anyhow::Result<Vec<WindowId>> {
+        let windows = self.windows.borrow();
+        Ok(self
+            .z_order
+            .borrow()
+            .iter()
+            .copied()
+            .filter(|window| windows[window].visible)
+            .collect())
+    }
+
+    fn monitors(&self) -> anyhow::Result<Vec<Monitor>> {
+        Ok(self.monitors.clone())
+    }
+
+    fn monitor_of(&self, window: WindowId) -> anyhow::Result<MonitorId> {
+        let monitor = self.with_window(window, |window| window.monitor)?;
+        Ok(self.monitors[monitor].id)
+    }
+
+    fn focused_window(&self) -> Option<WindowId> {
+        self.focused.get()
+    }
+
+    fn focus(&self, window: WindowId) -> anyhow::Result<()> {
+        self.with_window(window, |_| ())?;
+        self.raise(window);
+        Ok(())
+    }
+
+    fn window_rect(&self, window: WindowId) -> anyhow::Result<Rectangle> {
+        self.with_window(window, |window| window.rect)
+    }
+
+    /// Shows the window as well, like restoring it does on Windows
+    fn move_window(&self, window: WindowId, rect: Rectangle) -> anyhow::Result<()> {
+        self.moves.set(self.moves.get() + 1);
+        self.with_window(window, |window| {
+            window.rect = rect;
+            window.visible = true;
+        })
+    }
+
+    fn set_visible(&self, window: WindowId, visible: bool) -> anyhow::Result<()> {
+        self.with_window(window, |window| window.visible = visible)
+    }
+
+    fn is_valid(&self, window: WindowId) -> bool {
+        self.windows.borrow().contains_key(&window)
+    }
+
+    fn title(&self, window: WindowId) -> anyhow::Result<Option<String>> {
+        self.with_window(window, |window| window.title.clone())
+    }
+
+    fn class(&self, window: WindowId) -> anyhow::Result<String> {
+        self.with_window(window, |window| window.class.clone())
+    }
+
+    fn process_name(&self, window: WindowId) -> anyhow::Result<String> {
+        self.with_window(window, |window| window.process_name.clone())
+    }
+}
+
+/// Strip laid out by the tests, with half-screen columns 20 pixels apart and 10 pixels from the
+/// edges of `monitor`
+pub fn tiler_on(
+    monitor: &Monitor,
+    scroll_policy: ScrollPolicy,
+    insert_position: InsertPosition,
+) -> ScrollTiler {
+    ScrollTiler::new(
+        Gaps {
+            inner: 20,
+            outer: 10,
+            ..Default::default()
+        },
+        vec![0.5, 1.0],
+        0.5,
+        scroll_policy,
+        insert_position,
+        monitor,
+    )
+}
+
+/// Strip on the monitor of `FakeBackend::new`, new columns going to its end
+pub fn tiler(scroll_policy: ScrollPolicy) -> ScrollTiler {
+    tiler_on(
+        &FakeBackend::new().monitors[0],
+        scroll_policy,
+        InsertPosition::End,
+    )
+}
+
+/// Takes snapshots of the windows of a `FakeBackend`, placing the new ones by default
+pub trait Snapshot {
+    fn handle_snapshot(&mut self, backend: &FakeBackend);
+}
+
+impl Snapshot for ScrollTiler {
+    fn handle_snapshot(&mut self, backend: &FakeBackend) {
+        self.handle_window_snapshot(backend, &backend.opened_windows().unwrap(), &|_| {
+            Placement::default()
+        });
+    }
+}
+
+impl Snapshot for Workspaces {
+    fn handle_snapshot(&mut self, backend: &FakeBackend) {
+        self.handle_window_snapshot(backend, &backend.opened_windows().unwrap(), &|_| {
+            Placement::default()
+        });
+    }
+}
+
+impl Snapshot for Desktop {
+    fn handle_snapshot(&mut self, backend: &FakeBackend) {
+        self.handle_window_snapshot(backend, &backend.opened_windows().unwrap());
+    }
+}
+
+/// Gives the windows opened in `backend` to `target`, like the event loop does after a window
+/// event
+pub fn sync(target: &mut impl Snapshot, backend: &FakeBackend) {
+    target.handle_snapshot(backend);
+}
@@ -0,0 +1,92 @@
+pub mod cache;
+#[cfg(test)]
+pub mod fake;
+#[cfg(windows)]
+pub mod win32;
+
+use serde::{Deserialize, Serialize};
+
+use crate::hook::WindowEvent;
+
+/// Platform independent window handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct WindowId(pub isize);
+
+/// Platform independent monitor handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MonitorId(pub isize);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Rectangle {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rectangle {
+    pub const fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+}
+
+/// Dots per inch of a monitor at 100% scaling
+pub const DEFAULT_DPI: u32 = 96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Monitor {
+    pub id: MonitorId,
+    pub rect: Rectangle,
+    /// Part of the monitor not covered by the taskbar and docked toolbars
+    pub work_area: Rectangle,
+    /// Dots per inch, `DEFAULT_DPI` times the scaling set in the display settings
+    pub dpi: u32,
+}
+
+/// Everything the tiler needs from the windowing system
+pub trait Backend {
+    /// Returns the windows that should be tiled, from the top to the bottom of the z-order
+    fn opened_windows(&self) -> anyhow::Result<Vec<WindowId>>;
+
+    /// Returns every monitor from left to right
+    fn monitors(&self) -> anyhow::Result<Vec<Monitor>>;
+
+    /// Returns the monitor the largest part of `window` is on
+    fn monitor_of(&self, window: WindowId) -> anyhow::Result<MonitorId>;
+
+    fn focused_window(&self) -> Option<WindowId>;
+
+    fn focus(&self, window: WindowId) -> anyhow::Result<()>;
+
+    /// Returns the visible bounds of the window
+    fn window_rect(&self, window: WindowId) -> anyhow::Result<Rectangle>;
+
+    fn move_window(&self, window: WindowId, rect: Rectangle) -> anyhow::Result<()>;
+
+    fn set_visible(&self, window: WindowId, visible: bool) -> anyhow::Result<()>;
+
+    /// Returns false once the window has been destroyed
+    fn is_valid(&self, window: WindowId) -> bool;
+
+    fn title(&self, window: WindowId) -> anyhow::Result<Option<String>>;
+
+    fn class(&self, window: WindowId) -> anyhow::Result<String>;
+
+    fn process_name(&self, window: WindowId) -> anyhow::Result<String>;
+
+    /// Forgets the cached properties of the window that `event` may have changed
+    fn window_changed(&self, _event: &WindowEvent) {}
+
+    /// Forgets the cached focused window, at the start of every layout pass
+    fn forget_focus(&self) {}
+
+    /// Returns a human readable dump of the window properties, for logging purposes
+    fn describe(&self, window: WindowId) -> String {
+        format!(
+            "Window {window:?} info:\n\ttitle: {:?}\n\tclass: {:?}\n\tprocess_name: {:?}",
+            self.title(window),
+            self.class(window),
+            self.process_name(window),
+        )
+    }
+}
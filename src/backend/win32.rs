@@ -0,0 +1,118 @@
+use std::ffi::c_void;
+
+use windows::Win32::Foundation::HWND;
+
+use crate::{
+    backend::{Backend, Monitor, MonitorId, Rectangle, WindowId, cache::PropertyCache},
+    hook::WindowEvent,
+    screen,
+    window::{
+        Window,
+        filter::{WindowFilter, opened_windows},
+    },
+};
+
+impl From<Window> for WindowId {
+    fn from(window: Window) -> Self {
+        Self(window.handle().0 as isize)
+    }
+}
+
+impl From<WindowId> for Window {
+    fn from(window: WindowId) -> Self {
+        Self {
+            hwnd: HWND(window.0 as *mut c_void),
+        }
+    }
+}
+
+fn as_window(window: WindowId) -> Window {
+    window.into()
+}
+
+pub struct Win32Backend {
+    filter: WindowFilter,
+    cache: PropertyCache,
+}
+
+impl Win32Backend {
+    pub fn new(filter: WindowFilter) -> Self {
+        Self {
+            filter,
+            cache: PropertyCache::default(),
+        }
+    }
+}
+
+impl Backend for Win32Backend {
+    fn opened_windows(&self) -> anyhow::Result<Vec<WindowId>> {
+        Ok(opened_windows(&self.filter, &self.cache)?
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    fn monitors(&self) -> anyhow::Result<Vec<Monitor>> {
+        screen::monitors()
+    }
+
+    fn monitor_of(&self, window: WindowId) -> anyhow::Result<MonitorId> {
+        screen::monitor_of(window.into())
+    }
+
+    fn focused_window(&self) -> Option<WindowId> {
+        self.cache
+            .focused_window(|| Window::focused().ok().map(Into::into))
+    }
+
+    fn focus(&self, window: WindowId) -> anyhow::Result<()> {
+        self.cache.forget_focus();
+        as_window(window).focus()
+    }
+
+    fn window_rect(&self, window: WindowId) -> anyhow::Result<Rectangle> {
+        Ok(as_window(window).desktop_manager_rect()?.into())
+    }
+
+    fn move_window(&self, window: WindowId, rect: Rectangle) -> anyhow::Result<()> {
+        as_window(window).move_window(rect.x, rect.y, rect.width, rect.height)
+    }
+
+    fn set_visible(&self, window: WindowId, visible: bool) -> anyhow::Result<()> {
+        let window = as_window(window);
+        if visible {
+            window.show()
+        } else {
+            window.hide()
+        }
+    }
+
+    fn is_valid(&self, window: WindowId) -> bool {
+        as_window(window).is_valid().unwrap_or(false)
+    }
+
+    fn title(&self, window: WindowId) -> anyhow::Result<Option<String>> {
+        self.cache.title(window, || as_window(window).title())
+    }
+
+    fn class(&self, window: WindowId) -> anyhow::Result<String> {
+        self.cache.class(window, || as_window(window).class())
+    }
+
+    fn process_name(&self, window: WindowId) -> anyhow::Result<String> {
+        self.cache
+            .process_name(window, || as_window(window).process_name())
+    }
+
+    fn window_changed(&self, event: &WindowEvent) {
+        self.cache.window_changed(event);
+    }
+
+    fn forget_focus(&self) {
+        self.cache.forget_focus();
+    }
+
+    fn describe(&self, window: WindowId) -> String {
+        as_window(window).get_formatted_extensive_info()
+    }
+}
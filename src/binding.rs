@@ -0,0 +1,428 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+use anyhow::{Context, anyhow, bail};
+use rdev::Key;
+use serde::Deserialize;
+
+use crate::hook::key::Modifiers;
+
+const MODIFIER_NAMES: &[(&str, Modifiers)] = &[
+    ("Win", Modifiers::WIN),
+    ("Super", Modifiers::WIN),
+    ("Ctrl", Modifiers::CTRL),
+    ("Control", Modifiers::CTRL),
+    ("Alt", Modifiers::ALT),
+    ("Shift", Modifiers::SHIFT),
+];
+
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("A", Key::KeyA),
+    ("B", Key::KeyB),
+    ("C", Key::KeyC),
+    ("D", Key::KeyD),
+    ("E", Key::KeyE),
+    ("F", Key::KeyF),
+    ("G", Key::KeyG),
+    ("H", Key::KeyH),
+    ("I", Key::KeyI),
+    ("J", Key::KeyJ),
+    ("K", Key::KeyK),
+    ("L", Key::KeyL),
+    ("M", Key::KeyM),
+    ("N", Key::KeyN),
+    ("O", Key::KeyO),
+    ("P", Key::KeyP),
+    ("Q", Key::KeyQ),
+    ("R", Key::KeyR),
+    ("S", Key::KeyS),
+    ("T", Key::KeyT),
+    ("U", Key::KeyU),
+    ("V", Key::KeyV),
+    ("W", Key::KeyW),
+    ("X", Key::KeyX),
+    ("Y", Key::KeyY),
+    ("Z", Key::KeyZ),
+    ("0", Key::Num0),
+    ("1", Key::Num1),
+    ("2", Key::Num2),
+    ("3", Key::Num3),
+    ("4", Key::Num4),
+    ("5", Key::Num5),
+    ("6", Key::Num6),
+    ("7", Key::Num7),
+    ("8", Key::Num8),
+    ("9", Key::Num9),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+    ("F9", Key::F9),
+    ("F10", Key::F10),
+    ("F11", Key::F11),
+    ("F12", Key::F12),
+    ("Left", Key::LeftArrow),
+    ("Right", Key::RightArrow),
+    ("Up", Key::UpArrow),
+    ("Down", Key::DownArrow),
+    ("Space", Key::Space),
+    ("Enter", Key::Return),
+    ("Return", Key::Return),
+    ("Tab", Key::Tab),
+    ("Escape", Key::Escape),
+    ("Backspace", Key::Backspace),
+    ("Delete", Key::Delete),
+    ("Insert", Key::Insert),
+    ("Home", Key::Home),
+    ("End", Key::End),
+    ("PageUp", Key::PageUp),
+    ("PageDown", Key::PageDown),
+    ("Minus", Key::Minus),
+    ("Equal", Key::Equal),
+    ("Comma", Key::Comma),
+    ("Dot", Key::Dot),
+    ("Slash", Key::Slash),
+    ("BackSlash", Key::BackSlash),
+    ("SemiColon", Key::SemiColon),
+    ("Quote", Key::Quote),
+    ("BackQuote", Key::BackQuote),
+    ("LeftBracket", Key::LeftBracket),
+    ("RightBracket", Key::RightBracket),
+];
+
+/// Parses a binding such as `Win+Ctrl+Left`, names being case insensitive
+pub fn parse_binding(binding: &str) -> anyhow::Result<(Modifiers, Key)> {
+    let mut parts = binding.split('+').map(str::trim).collect::<Vec<_>>();
+    let key_name = parts
+        .pop()
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow!("Binding `{binding}` has no key"))?;
+
+    let mut modifiers = Modifiers::empty();
+    for name in parts {
+        let (_, modifier) = MODIFIER_NAMES
+            .iter()
+            .find(|(modifier_name, _)| modifier_name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| anyhow!("Unknown modifier `{name}` in binding `{binding}`"))?;
+        modifiers.insert(*modifier);
+    }
+
+    let key = KEY_NAMES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key_name))
+        .map(|(_, key)| *key)
+        .ok_or_else(|| {
+            if MODIFIER_NAMES
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(key_name))
+            {
+                anyhow!("Binding `{binding}` has no key, only modifiers")
+            } else {
+                anyhow!("Unknown key `{key_name}` in binding `{binding}`")
+            }
+        })?;
+
+    Ok((modifiers, key))
+}
+
+/// Command a binding triggers, workspaces being referred to by index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    FocusLeft,
+    FocusRight,
+    FocusUp,
+    FocusDown,
+    SwapLeft,
+    SwapRight,
+    SwapUp,
+    SwapDown,
+    ConsumeOrExpelLeft,
+    ConsumeOrExpelRight,
+    CycleWidth,
+    /// Proportion of the screen width
+    SetWidth(f32),
+    CycleScrollPolicy,
+    ToggleFloating,
+    ToggleMaximized,
+    ToggleFullscreen,
+    /// Shows the whole strip scaled down to pick a column with the arrow keys
+    ToggleOverview,
+    FocusMonitorLeft,
+    FocusMonitorRight,
+    MoveToMonitorLeft,
+    MoveToMonitorRight,
+    SwitchToWorkspace(usize),
+    MoveToWorkspace(usize),
+    MoveToWorkspaceAndFollow(usize),
+    /// Puts every window back in view and exits winri
+    Quit,
+}
+
+impl FromStr for Action {
+    type Err = anyhow::Error;
+
+    /// Parses an action such as `focus-left` or `set-width 0.5`, workspaces being numbered
+    /// from 1
+    fn from_str(action: &str) -> anyhow::Result<Self> {
+        let mut words = action.split_whitespace();
+        let name = words.next().ok_or_else(|| anyhow!("Empty action"))?;
+        let argument = words.next();
+        if let Some(extra) = words.next() {
+            bail!("Unexpected `{extra}` after action `{name}`");
+        }
+
+        let width = || -> anyhow::Result<f32> {
+            let argument = argument.ok_or_else(|| anyhow!("`{name}` needs a width"))?;
+            let width = argument
+                .parse::<f32>()
+                .with_context(|| format!("Invalid width `{argument}` for `{name}`"))?;
+            anyhow::ensure!(
+                width > 0.0 && width <= 1.0,
+                "Width of `{name}` is a proportion of the screen and must be in (0, 1], got {width}"
+            );
+            Ok(width)
+        };
+        let workspace = || -> anyhow::Result<usize> {
+            let argument = argument.ok_or_else(|| anyhow!("`{name}` needs a workspace number"))?;
+            match argument.parse::<usize>() {
+                Ok(number) if number > 0 => Ok(number - 1),
+                _ => bail!("Invalid workspace `{argument}` for `{name}`, they are numbered from 1"),
+            }
+        };
+
+        let action = match name {
+            "set-width" => return Ok(Self::SetWidth(width()?)),
+            "switch-to-workspace" => return Ok(Self::SwitchToWorkspace(workspace()?)),
+            "move-to-workspace" => return Ok(Self::MoveToWorkspace(workspace()?)),
+            "move-to-workspace-and-follow" => {
+                return Ok(Self::MoveToWorkspaceAndFollow(workspace()?));
+            }
+            "focus-left" => Self::FocusLeft,
+            "focus-right" => Self::FocusRight,
+            "focus-up" => Self::FocusUp,
+            "focus-down" => Self::FocusDown,
+            "swap-left" => Self::SwapLeft,
+            "swap-right" => Self::SwapRight,
+            "swap-up" => Self::SwapUp,
+            "swap-down" => Self::SwapDown,
+            "consume-or-expel-left" => Self::ConsumeOrExpelLeft,
+            "consume-or-expel-right" => Self::ConsumeOrExpelRight,
+            "cycle-width" => Self::CycleWidth,
+            "cycle-scroll-policy" => Self::CycleScrollPolicy,
+            "toggle-floating" => Self::ToggleFloating,
+            "toggle-maximized" => Self::ToggleMaximized,
+            "toggle-fullscreen" => Self::ToggleFullscreen,
+            "toggle-overview" => Self::ToggleOverview,
+            "focus-monitor-left" => Self::FocusMonitorLeft,
+            "focus-monitor-right" => Self::FocusMonitorRight,
+            "move-to-monitor-left" => Self::MoveToMonitorLeft,
+            "move-to-monitor-right" => Self::MoveToMonitorRight,
+            "quit" => Self::Quit,
+            _ => bail!("Unknown action `{name}`"),
+        };
+        if let Some(argument) = argument {
+            bail!("Action `{name}` takes no argument, got `{argument}`");
+        }
+        Ok(action)
+    }
+}
+
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("Win+Left", "focus-left"),
+    ("Win+Right", "focus-right"),
+    ("Win+Up", "focus-up"),
+    ("Win+Down", "focus-down"),
+    ("Win+Ctrl+Left", "swap-left"),
+    ("Win+Ctrl+Right", "swap-right"),
+    ("Win+Ctrl+Up", "swap-up"),
+    ("Win+Ctrl+Down", "swap-down"),
+    ("Win+Shift+Left", "consume-or-expel-left"),
+    ("Win+Shift+Right", "consume-or-expel-right"),
+    ("Win+R", "cycle-width"),
+    ("Win+C", "cycle-scroll-policy"),
+    ("Win+V", "toggle-floating"),
+    ("Win+F", "toggle-maximized"),
+    ("Win+Shift+F", "toggle-fullscreen"),
+    ("Win+O", "toggle-overview"),
+    ("Win+Alt+Left", "focus-monitor-left"),
+    ("Win+Alt+Right", "focus-monitor-right"),
+    ("Win+Alt+Shift+Left", "move-to-monitor-left"),
+    ("Win+Alt+Shift+Right", "move-to-monitor-right"),
+];
+
+/// Action value removing a binding, such as a default one
+const UNBOUND: &str = "none";
+
+/// Number of workspaces reachable through the default `Win+<number>` bindings
+const DEFAULT_WORKSPACE_BINDINGS: usize = 9;
+
+/// Lookup table from key combinations to actions
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct Bindings(HashMap<(Modifiers, Key), Action>);
+
+impl Bindings {
+    pub fn get(&self, modifiers: Modifiers, key: Key) -> Option<Action> {
+        self.0.get(&(modifiers, key)).copied()
+    }
+
+    /// Parses and adds `binding`, replacing the action previously bound to the same keys, or
+    /// removes it when `action` is `"none"`
+    pub fn insert(&mut self, binding: &str, action: &str) -> anyhow::Result<()> {
+        let keys = parse_binding(binding)?;
+        if action == UNBOUND {
+            self.0.remove(&keys);
+            return Ok(());
+        }
+        let action = action
+            .parse()
+            .with_context(|| format!("Invalid action for binding `{binding}`"))?;
+        self.0.insert(keys, action);
+        Ok(())
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let mut bindings = Self(HashMap::new());
+        let workspace_bindings = (1..=DEFAULT_WORKSPACE_BINDINGS).flat_map(|number| {
+            [
+                (
+                    format!("Win+{number}"),
+                    format!("switch-to-workspace {number}"),
+                ),
+                (
+                    format!("Win+Shift+{number}"),
+                    format!("move-to-workspace {number}"),
+                ),
+                (
+                    format!("Win+Ctrl+{number}"),
+                    format!("move-to-workspace-and-follow {number}"),
+                ),
+            ]
+        });
+        for (binding, action) in DEFAULT_BINDINGS
+            .iter()
+            .map(|(binding, action)| ((*binding).to_string(), (*action).to_string()))
+            .chain(workspace_bindings)
+        {
+            bindings
+                .insert(&binding, &action)
+                .expect("default bindings are valid");
+        }
+        bindings
+    }
+}
+
+/// Bindings from the configuration are added on top of the default ones
+impl TryFrom<BTreeMap<String, String>> for Bindings {
+    type Error = anyhow::Error;
+
+    fn try_from(overrides: BTreeMap<String, String>) -> anyhow::Result<Self> {
+        let mut bindings = Self::default();
+        for (binding, action) in &overrides {
+            bindings.insert(binding, action)?;
+        }
+        Ok(bindings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_are_parsed_case_insensitively() {
+        assert_eq!(
+            parse_binding("Win+Ctrl+Left").unwrap(),
+            (Modifiers::WIN | Modifiers::CTRL, Key::LeftArrow)
+        );
+        assert_eq!(
+            parse_binding("super + shift + f").unwrap(),
+            (Modifiers::WIN | Modifiers::SHIFT, Key::KeyF)
+        );
+        assert_eq!(parse_binding("F5").unwrap(), (Modifiers::empty(), Key::F5));
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let error = |binding: &str| parse_binding(binding).unwrap_err().to_string();
+        assert_eq!(error("Win+Foo"), "Unknown key `Foo` in binding `Win+Foo`");
+        assert_eq!(
+            error("Hyper+A"),
+            "Unknown modifier `Hyper` in binding `Hyper+A`"
+        );
+        assert_eq!(
+            error("Win+Ctrl"),
+            "Binding `Win+Ctrl` has no key, only modifiers"
+        );
+        assert_eq!(error("Win+"), "Binding `Win+` has no key");
+    }
+
+    #[test]
+    fn actions_are_parsed_with_their_argument() {
+        assert_eq!("swap-right".parse::<Action>().unwrap(), Action::SwapRight);
+        assert_eq!(
+            "set-width 0.5".parse::<Action>().unwrap(),
+            Action::SetWidth(0.5)
+        );
+        assert_eq!(
+            "switch-to-workspace 3".parse::<Action>().unwrap(),
+            Action::SwitchToWorkspace(2)
+        );
+        assert_eq!("quit".parse::<Action>().unwrap(), Action::Quit);
+        assert!("set-width 2".parse::<Action>().is_err());
+        assert!("switch-to-workspace 0".parse::<Action>().is_err());
+        assert!("focus-left 1".parse::<Action>().is_err());
+        assert!("teleport".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn configured_bindings_remove_defaults_bound_to_none() {
+        let bindings = Bindings::try_from(BTreeMap::from([
+            ("Win+O".to_string(), "none".to_string()),
+            ("Win+Y".to_string(), "none".to_string()),
+        ]))
+        .unwrap();
+        assert_eq!(bindings.get(Modifiers::WIN, Key::KeyO), None);
+        assert_eq!(bindings.get(Modifiers::WIN, Key::KeyY), None);
+        assert_eq!(
+            bindings.get(Modifiers::WIN, Key::KeyR),
+            Some(Action::CycleWidth)
+        );
+        assert!(
+            Bindings::try_from(BTreeMap::from([(
+                "Win+Foo".to_string(),
+                "none".to_string()
+            )]))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn configured_bindings_override_the_defaults() {
+        let bindings = Bindings::try_from(BTreeMap::from([(
+            "win+r".to_string(),
+            "set-width 0.5".to_string(),
+        )]))
+        .unwrap();
+        assert_eq!(
+            bindings.get(Modifiers::WIN, Key::KeyR),
+            Some(Action::SetWidth(0.5))
+        );
+        assert_eq!(
+            bindings.get(Modifiers::WIN, Key::LeftArrow),
+            Some(Action::FocusLeft)
+        );
+        assert_eq!(
+            bindings.get(Modifiers::WIN | Modifiers::ALT, Key::KeyR),
+            None
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::{fs, io, path::PathBuf};
+
+use anyhow::Context;
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::{
+    animation::Easing,
+    binding::Bindings,
+    rule::Rule,
+    tiler::{Edges, Gaps, InsertPosition, ScrollPolicy},
+};
+
+const CONFIG_FILE: &str = "config.toml";
+
+const DEFAULT_IGNORED_CLASSES: &[&str] = &[
+    "Progman",
+    "TopLevelWindowForOverflowXamlIsland",
+    "XamlExplorerHostIslandWindow",
+    "Xaml_WindowedPopupClass",
+    "Shell_TrayWnd",
+];
+
+const DEFAULT_IGNORED_PROCESSES: &[&str] = &[
+    "Microsoft.CmdPal.UI.exe",
+    "PowerToys.MeasureToolUI.exe",
+    "ShareX.exe",
+    "SnippingTool.exe",
+    "PowerToys.PowerLauncher.exe",
+    "Ditto.exe",
+];
+
+/// User settings read from `config.toml`, every missing key keeping its default value
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Space between two columns and between the windows of a column, in logical pixels scaled
+    /// by the DPI of each monitor. The default keeps the columns as far apart as the padding
+    /// used to, which leaves twice its space between the windows of a column.
+    pub inner_gap: i32,
+    /// Space between the windows and the edges of the screen, in logical pixels
+    pub outer_gap: i32,
+    /// Deprecated single spacing from before the gaps were split, read as `outer-gap = padding`
+    /// and `inner-gap = 2 * padding`, which keeps the columns where they were but doubles the
+    /// space between the windows of a column
+    pub padding: Option<i32>,
+    /// Logical pixels left free on each edge of the screen, for example for a status bar
+    pub reserved: Edges,
+    /// Proportions of the screen width a column can be cycled through from the smallest to the
+    /// largest, which makes them independent of the DPI
+    pub width_presets: Vec<f32>,
+    /// Proportion of the screen width given to new columns
+    pub default_width: f32,
+    pub scroll_policy: ScrollPolicy,
+    pub insert_position: InsertPosition,
+    /// Milliseconds windows take to reach their new position, 0 moving them right away
+    pub animation_duration: u64,
+    pub animation_easing: Easing,
+    /// Milliseconds after enumerating the windows during which the window events asking for it
+    /// again are coalesced into a single enumeration
+    pub window_event_debounce: u64,
+    /// Focuses the tiled window under the pointer once it rests there
+    pub focus_follows_mouse: bool,
+    /// Milliseconds the pointer has to rest on a window before it is focused
+    pub focus_follows_mouse_delay: u64,
+    /// Window classes that are never tiled
+    pub ignored_classes: Vec<String>,
+    /// Executable names whose windows are never tiled
+    pub ignored_processes: Vec<String>,
+    /// Key combinations such as `"Win+Ctrl+Left"` mapped to actions such as `"swap-left"`,
+    /// added on top of the default bindings, `"none"` removing one of them
+    pub bindings: Bindings,
+    /// Rules deciding how matching windows are managed, applied in order
+    pub rules: Vec<Rule>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            // Twice the outer gap, like the padding of both neighbours used to add up
+            inner_gap: 20,
+            outer_gap: 10,
+            padding: None,
+            reserved: Edges::default(),
+            width_presets: vec![1.0 / 3.0, 1.0 / 2.0, 2.0 / 3.0, 1.0],
+            default_width: 2.0 / 3.0,
+            scroll_policy: ScrollPolicy::default(),
+            insert_position: InsertPosition::default(),
+            animation_duration: 150,
+            animation_easing: Easing::default(),
+            window_event_debounce: 100,
+            focus_follows_mouse: false,
+            focus_follows_mouse_delay: 150,
+            ignored_classes: DEFAULT_IGNORED_CLASSES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            ignored_processes: DEFAULT_IGNORED_PROCESSES
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            bindings: Bindings::default(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Returns `winri/config.toml` in the user's configuration directory
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("winri").join(CONFIG_FILE))
+    }
+
+    /// Reads the configuration file, falling back to the defaults when there is none
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path() else {
+            info!("No configuration directory, using the default configuration");
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                info!("Loading configuration from {}", path.display());
+                Self::parse(&text).with_context(|| format!("Invalid config {}", path.display()))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                info!(
+                    "No configuration at {}, using the default configuration",
+                    path.display()
+                );
+                Ok(Self::default())
+            }
+            Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut config: Self = toml::from_str(text)?;
+        if let Some(padding) = config.padding.take() {
+            let defaults = Self::default();
+            anyhow::ensure!(
+                config.inner_gap == defaults.inner_gap && config.outer_gap == defaults.outer_gap,
+                "padding is replaced by inner-gap and outer-gap and can not be combined with them"
+            );
+            warn!(
+                "padding is deprecated, use outer-gap = {padding} and inner-gap = {} instead",
+                padding * 2
+            );
+            config.outer_gap = padding;
+            config.inner_gap = padding * 2;
+        }
+        let reserved = config.reserved;
+        for (name, length) in [
+            ("inner-gap", config.inner_gap),
+            ("outer-gap", config.outer_gap),
+            ("reserved.top", reserved.top),
+            ("reserved.bottom", reserved.bottom),
+            ("reserved.left", reserved.left),
+            ("reserved.right", reserved.right),
+        ] {
+            anyhow::ensure!(length >= 0, "{name} must not be negative, got {length}");
+        }
+        anyhow::ensure!(
+            !config.width_presets.is_empty(),
+            "width-presets must list at least one width"
+        );
+        // Cycling goes to the next larger preset
+        config.width_presets.sort_by(f32::total_cmp);
+        config
+            .width_presets
+            .dedup_by(|a, b| (*a - *b).abs() < f32::EPSILON);
+        for width in config.width_presets.iter().chain([&config.default_width]) {
+            anyhow::ensure!(
+                *width > 0.0 && *width <= 1.0,
+                "widths are proportions of the screen and must be in (0, 1], got {width}"
+            );
+        }
+        for (index, rule) in config.rules.iter().enumerate() {
+            rule.validate()
+                .with_context(|| format!("Invalid rule #{}", index + 1))?;
+        }
+        Ok(config)
+    }
+
+    pub const fn gaps(&self) -> Gaps {
+        Gaps {
+            inner: self.inner_gap,
+            outer: self.outer_gap,
+            reserved: self.reserved,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_keys_keep_their_defaults() {
+        let config =
+            Config::parse("inner-gap = 4\nscroll-policy = \"center\"\n[reserved]\ntop = 30")
+                .unwrap();
+        assert_eq!(
+            config,
+            Config {
+                inner_gap: 4,
+                reserved: Edges {
+                    top: 30,
+                    ..Default::default()
+                },
+                scroll_policy: ScrollPolicy::Center,
+                ..Default::default()
+            }
+        );
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn default_bindings_can_be_removed() {
+        let config = Config::parse("[bindings]\n\"Win+O\" = \"none\"").unwrap();
+        assert_eq!(
+            config
+                .bindings
+                .get(crate::hook::key::Modifiers::WIN, rdev::Key::KeyO),
+            None
+        );
+    }
+
+    #[test]
+    fn width_presets_are_sorted() {
+        let config = Config::parse("width-presets = [1.0, 0.5, 1.0]").unwrap();
+        assert_eq!(config.width_presets, [0.5, 1.0]);
+    }
+
+    #[test]
+    fn padding_is_read_as_gaps() {
+        let config = Config::parse("padding = 5").unwrap();
+        assert_eq!((config.inner_gap, config.outer_gap), (10, 5));
+        assert_eq!(config.padding, None);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        assert!(Config::parse("default-width = 1.5").is_err());
+        assert!(Config::parse("width-presets = []").is_err());
+        assert!(Config::parse("outer-gap = -1").is_err());
+        assert!(Config::parse("[reserved]\nleft = -1").is_err());
+        assert!(Config::parse("[reserved]\nmiddle = 1").is_err());
+        assert!(Config::parse("scroll-policy = \"sideways\"").is_err());
+        assert!(Config::parse("unknown = 1").is_err());
+        assert!(Config::parse("padding = -1").is_err());
+        assert!(Config::parse("padding = 5\nouter-gap = 4").is_err());
+        assert!(Config::parse("[bindings]\n\"Win+Foo\" = \"focus-left\"").is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+
+use crate::animation::Clock;
+
+/// Coalesces bursts of triggers into at most one dispatch per `window`.
+///
+/// The first trigger after a quiet period dispatches right away, and the ones arriving within
+/// `window` of a dispatch are folded into a single dispatch once it elapses.
+pub struct Debouncer<C> {
+    clock: C,
+    window: Duration,
+    last_dispatch: Option<Instant>,
+    pending: bool,
+}
+
+impl<C: Clock> Debouncer<C> {
+    pub const fn new(clock: C, window: Duration) -> Self {
+        Self {
+            clock,
+            window,
+            last_dispatch: None,
+            pending: false,
+        }
+    }
+
+    /// Returns whether to dispatch right away, otherwise the dispatch waits for `deadline`
+    pub fn trigger(&mut self) -> bool {
+        let now = self.clock.now();
+        if self
+            .last_dispatch
+            .is_some_and(|last_dispatch| now < last_dispatch + self.window)
+        {
+            self.pending = true;
+            false
+        } else {
+            self.last_dispatch = Some(now);
+            self.pending = false;
+            true
+        }
+    }
+
+    /// Returns when the held back triggers are due to be dispatched
+    pub fn deadline(&self) -> Option<Instant> {
+        self.last_dispatch
+            .filter(|_| self.pending)
+            .map(|last_dispatch| last_dispatch + self.window)
+    }
+
+    /// Returns whether the held back triggers are due, the caller dispatching them
+    pub fn take_due(&mut self) -> bool {
+        let now = self.clock.now();
+        if self.deadline().is_some_and(|deadline| deadline <= now) {
+            self.last_dispatch = Some(now);
+            self.pending = false;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::animation::ManualClock;
+
+    #[test]
+    fn bursts_dispatch_on_both_edges() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let mut debouncer = Debouncer::new(&clock, Duration::from_millis(100));
+
+        assert!(debouncer.trigger());
+        assert_eq!(debouncer.deadline(), None);
+
+        clock.advance(Duration::from_millis(30));
+        assert!(!debouncer.trigger());
+        clock.advance(Duration::from_millis(30));
+        assert!(!debouncer.trigger());
+        assert!(!debouncer.take_due());
+
+        clock.advance(Duration::from_millis(40));
+        assert!(debouncer.take_due());
+        assert!(!debouncer.take_due());
+
+        // Quiet for a whole window, the next trigger dispatches right away
+        clock.advance(Duration::from_millis(100));
+        assert!(debouncer.trigger());
+    }
+
+    #[test]
+    fn held_back_triggers_flush_between_other_events() {
+        let clock = ManualClock(Cell::new(Instant::now()));
+        let mut debouncer = Debouncer::new(&clock, Duration::from_millis(100));
+        assert!(debouncer.trigger());
+        clock.advance(Duration::from_millis(10));
+        assert!(!debouncer.trigger());
+
+        // Other events keep coming faster than the window, the loop checking after each of them
+        let mut flushed_at = None;
+        for event in 1..=10 {
+            clock.advance(Duration::from_millis(30));
+            if debouncer.take_due() {
+                flushed_at.get_or_insert(event);
+            }
+        }
+        assert_eq!(flushed_at, Some(3));
+        assert_eq!(debouncer.deadline(), None);
+    }
+}
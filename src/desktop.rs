@@ -0,0 +1,731 @@
+use std::collections::{HashMap, HashSet};
+
+use log::{info, warn};
+
+use crate::{
+    backend::{Backend, Monitor, WindowId},
+    event::StateEvent,
+    hook::{WindowEvent, WindowEventKind},
+    rule::{self, Rule, WindowProperties, WindowRules},
+    session::{self, SavedMonitor, SavedStrip, Session, WindowIdentity},
+    tiler::ScrollTiler,
+    utils::offset_index,
+    workspace::Workspaces,
+};
+
+struct MonitorState {
+    monitor: Monitor,
+    workspaces: Workspaces,
+}
+
+/// Rules of a window along with the title they were evaluated for
+struct RuledWindow {
+    title: Option<String>,
+    rules: WindowRules,
+}
+
+/// Every monitor with its own workspaces and scroll strips
+pub struct Desktop {
+    monitors: Vec<MonitorState>,
+    workspace_names: Vec<String>,
+    /// Empty strip copied into the workspaces of the monitors connected later
+    empty_tiler: ScrollTiler,
+    rules: Vec<Rule>,
+    ruled_windows: HashMap<WindowId, RuledWindow>,
+}
+
+impl Desktop {
+    /// `monitors` must not be empty and is expected to be ordered from left to right
+    pub fn new(
+        monitors: Vec<Monitor>,
+        workspace_names: &[&str],
+        rules: Vec<Rule>,
+        tiler: impl Fn(&Monitor) -> ScrollTiler,
+    ) -> Self {
+        Self {
+            empty_tiler: tiler(&monitors[0]),
+            monitors: monitors
+                .into_iter()
+                .map(|monitor| MonitorState {
+                    workspaces: Workspaces::new(workspace_names, &tiler(&monitor)),
+                    monitor,
+                })
+                .collect(),
+            workspace_names: workspace_names.iter().map(ToString::to_string).collect(),
+            rules,
+            ruled_windows: HashMap::new(),
+        }
+    }
+
+    fn owning_monitor(&self, window: WindowId) -> Option<usize> {
+        self.monitors
+            .iter()
+            .position(|state| state.workspaces.contains(window))
+    }
+
+    /// Returns the index of the monitor the window is currently displayed on
+    fn monitor_of(&self, backend: &impl Backend, window: WindowId) -> Option<usize> {
+        let monitor = backend
+            .monitor_of(window)
+            .inspect_err(|err| warn!("Could not get monitor of window {window:?}: {err}"))
+            .ok()?;
+        self.monitors
+            .iter()
+            .position(|state| state.monitor.id == monitor)
+    }
+
+    fn focused_monitor(&self, backend: &impl Backend) -> usize {
+        backend
+            .focused_window()
+            .and_then(|window| {
+                self.owning_monitor(window)
+                    .or_else(|| self.monitor_of(backend, window))
+            })
+            .unwrap_or(0)
+    }
+
+    /// Returns the active strip of the focused monitor
+    pub fn active_tiler(&self, backend: &impl Backend) -> &ScrollTiler {
+        self.monitors[self.focused_monitor(backend)]
+            .workspaces
+            .active_tiler()
+    }
+
+    /// Returns the strip holding `window`, whatever its workspace
+    pub fn tiler_of_mut(&mut self, window: WindowId) -> Option<&mut ScrollTiler> {
+        self.monitors
+            .iter_mut()
+            .find_map(|state| state.workspaces.tiler_of_mut(window))
+    }
+
+    /// Returns the active strip of the monitor containing the point
+    pub fn tiler_at_mut(&mut self, x: i32, y: i32) -> Option<&mut ScrollTiler> {
+        self.monitors
+            .iter_mut()
+            .find(|state| state.monitor.rect.contains(x, y))
+            .map(|state| state.workspaces.active_tiler_mut())
+    }
+
+    pub fn active_workspaces_mut(&mut self, backend: &impl Backend) -> &mut Workspaces {
+        let index = self.focused_monitor(backend);
+        &mut self.monitors[index].workspaces
+    }
+
+    pub fn active_tiler_mut(&mut self, backend: &impl Backend) -> &mut ScrollTiler {
+        self.active_workspaces_mut(backend).active_tiler_mut()
+    }
+
+    /// Hands the active strip over to the overview, or gives every strip back its layout
+    pub fn set_overview(&mut self, backend: &impl Backend, shown: bool) {
+        for state in &mut self.monitors {
+            for tiler in state.workspaces.tilers_mut() {
+                tiler.set_in_overview(false);
+            }
+        }
+        if shown {
+            self.active_tiler_mut(backend).set_in_overview(true);
+        }
+    }
+
+    pub fn handle_window_snapshot(
+        &mut self,
+        backend: &impl Backend,
+        windows_snapshot: &[WindowId],
+    ) {
+        let windows_snapshot = self.apply_rules(backend, windows_snapshot);
+        let mut snapshots = vec![Vec::new(); self.monitors.len()];
+
+        for window in windows_snapshot {
+            // New windows go to the monitor they opened on
+            let index = if let Some(index) = self.owning_monitor(window) {
+                index
+            } else {
+                let index = self.monitor_of(backend, window).unwrap_or(0);
+                self.place_on_rule_workspace(index, window);
+                index
+            };
+            snapshots[index].push(window);
+        }
+
+        let ruled_windows = &self.ruled_windows;
+        let placement = |window| {
+            ruled_windows
+                .get(&window)
+                .map(|ruled| ruled.rules.placement)
+                .unwrap_or_default()
+        };
+        for (state, snapshot) in self.monitors.iter_mut().zip(&snapshots) {
+            state
+                .workspaces
+                .handle_window_snapshot(backend, snapshot, &placement);
+        }
+    }
+
+    /// Applies a window event to the strips without enumerating the windows, returning false when
+    /// it needs a new snapshot instead, such as a window showing up or being renamed
+    pub fn handle_window_event(&mut self, backend: &impl Backend, event: &WindowEvent) -> bool {
+        match event.kind {
+            WindowEventKind::Destroyed => {
+                self.ruled_windows.remove(&event.window);
+                for state in &mut self.monitors {
+                    state.workspaces.remove_window(backend, event.window);
+                }
+            }
+            WindowEventKind::Focused | WindowEventKind::MoveSizeEnded => {
+                for state in &mut self.monitors {
+                    let tiler = state.workspaces.active_tiler_mut();
+                    // Windows dragged out of the strip are put back in place
+                    if event.kind == WindowEventKind::MoveSizeEnded && tiler.contains(event.window)
+                    {
+                        tiler.request_layout();
+                    }
+                    tiler.refresh(backend);
+                }
+            }
+            WindowEventKind::MoveSizeStarted => {}
+            WindowEventKind::Created
+            | WindowEventKind::Shown
+            | WindowEventKind::Hidden
+            | WindowEventKind::Minimized
+            | WindowEventKind::Restored
+            | WindowEventKind::TitleChanged => return false,
+        }
+        true
+    }
+
+    /// Evaluates the rules of new windows and of windows whose title changed, returning the
+    /// windows of the snapshot that are not ignored
+    fn apply_rules(
+        &mut self,
+        backend: &impl Backend,
+        windows_snapshot: &[WindowId],
+    ) -> Vec<WindowId> {
+        let snapshot_set = windows_snapshot.iter().copied().collect::<HashSet<_>>();
+        self.ruled_windows
+            .retain(|window, _| snapshot_set.contains(window));
+
+        let mut managed_windows = Vec::with_capacity(windows_snapshot.len());
+        for window in windows_snapshot {
+            let title = backend.title(*window).unwrap_or_else(|err| {
+                warn!("Failed to get title of window {window:?}: {err}");
+                None
+            });
+            let previous = self.ruled_windows.get(window);
+            if previous.is_none_or(|ruled| ruled.title != title) {
+                let rules = rule::evaluate(
+                    &self.rules,
+                    &WindowProperties {
+                        class: backend.class(*window).unwrap_or_default(),
+                        process: backend.process_name(*window).unwrap_or_default(),
+                        title: title.clone(),
+                    },
+                );
+                if previous.is_some_and(|ruled| ruled.rules != rules) {
+                    info!("Rules of window {window:?} changed with its title to {rules:?}");
+                    if !rules.ignore
+                        && let Some(tiler) = self.tiler_of_mut(*window)
+                    {
+                        tiler.apply_placement(backend, *window, &rules.placement);
+                    }
+                }
+                self.ruled_windows
+                    .insert(*window, RuledWindow { title, rules });
+            }
+
+            if !self.ruled_windows[window].rules.ignore {
+                managed_windows.push(*window);
+            }
+        }
+        managed_windows
+    }
+
+    /// Puts a new window on the workspace its rules ask for, when it is not the active one
+    fn place_on_rule_workspace(&mut self, monitor: usize, window: WindowId) {
+        let Some(rules) = self.ruled_windows.get(&window).map(|ruled| ruled.rules) else {
+            return;
+        };
+        let Some(index) = rules.workspace else {
+            return;
+        };
+
+        let workspaces = &mut self.monitors[monitor].workspaces;
+        if index >= workspaces.len() {
+            warn!(
+                "Window {window:?} asks for workspace {} which does not exist",
+                index + 1
+            );
+        } else if index != workspaces.active_index() {
+            info!("Placing window {window:?} on workspace {}", index + 1);
+            workspaces.place_window(index, window, &rules.placement);
+        }
+    }
+
+    pub fn focus_monitor_left(&self, backend: &impl Backend) {
+        self.focus_monitor(backend, -1);
+    }
+
+    pub fn focus_monitor_right(&self, backend: &impl Backend) {
+        self.focus_monitor(backend, 1);
+    }
+
+    fn focus_monitor(&self, backend: &impl Backend, direction: i32) {
+        let index = self.focused_monitor(backend);
+        let other_index = offset_index(index, direction, self.monitors.len());
+        if other_index != index {
+            self.monitors[other_index]
+                .workspaces
+                .active_tiler()
+                .focus_active(backend);
+        }
+    }
+
+    pub fn move_focused_window_to_monitor_left(&mut self, backend: &impl Backend) {
+        self.move_focused_window_to_monitor(backend, -1);
+    }
+
+    pub fn move_focused_window_to_monitor_right(&mut self, backend: &impl Backend) {
+        self.move_focused_window_to_monitor(backend, 1);
+    }
+
+    fn move_focused_window_to_monitor(&mut self, backend: &impl Backend, direction: i32) {
+        let index = self.focused_monitor(backend);
+        let other_index = offset_index(index, direction, self.monitors.len());
+        if other_index == index {
+            return;
+        }
+
+        let Some((window, layer)) = self.monitors[index]
+            .workspaces
+            .active_tiler_mut()
+            .take_focused_window(backend)
+        else {
+            return;
+        };
+        info!("Moving window {window:?} to monitor {other_index}");
+        self.monitors[other_index]
+            .workspaces
+            .active_tiler_mut()
+            .insert_window(window, layer);
+    }
+
+    pub fn session(&self, backend: &impl Backend) -> Session {
+        Session {
+            monitors: self
+                .monitors
+                .iter()
+                .map(|state| SavedMonitor {
+                    active_workspace: state.workspaces.active_index(),
+                    workspaces: state
+                        .workspaces
+                        .tilers()
+                        .map(|tiler| SavedStrip::new(backend, tiler))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Puts the opened windows back in the strips they were saved in, before the first snapshot
+    /// inserts the remaining ones
+    pub fn restore_session(
+        &mut self,
+        backend: &impl Backend,
+        session: &Session,
+        windows: &[WindowId],
+    ) {
+        let opened = windows
+            .iter()
+            .filter_map(|window| {
+                WindowIdentity::of(backend, *window)
+                    .ok()
+                    .map(|identity| (*window, identity))
+            })
+            .collect::<Vec<_>>();
+        let saved = session
+            .monitors
+            .iter()
+            .flat_map(|monitor| &monitor.workspaces)
+            .flat_map(|strip| {
+                strip
+                    .columns
+                    .iter()
+                    .flat_map(|column| &column.windows)
+                    .chain(&strip.floating)
+            })
+            .collect::<Vec<_>>();
+        let mut matched = session::match_windows(&saved, &opened).into_iter();
+        info!(
+            "Restoring {} of the {} saved windows",
+            matched.clone().flatten().count(),
+            saved.len()
+        );
+
+        for (index, saved_monitor) in session.monitors.iter().enumerate() {
+            for (workspace, strip) in saved_monitor.workspaces.iter().enumerate() {
+                // Consumed even without a matching strip to keep the order of `saved`
+                let columns = strip
+                    .columns
+                    .iter()
+                    .map(|column| {
+                        let windows = matched.by_ref().take(column.windows.len()).flatten();
+                        (windows.collect::<Vec<_>>(), column.width)
+                    })
+                    .collect::<Vec<_>>();
+                let floating = matched
+                    .by_ref()
+                    .take(strip.floating.len())
+                    .flatten()
+                    .collect();
+                if let Some(tiler) = self
+                    .monitors
+                    .get_mut(index)
+                    .and_then(|state| state.workspaces.tiler_mut(workspace))
+                {
+                    tiler.restore(
+                        backend,
+                        columns,
+                        floating,
+                        strip.active_column,
+                        strip.scroll_offset,
+                    );
+                }
+            }
+            if let Some(state) = self.monitors.get_mut(index) {
+                state
+                    .workspaces
+                    .switch_to(backend, saved_monitor.active_workspace);
+            }
+        }
+    }
+
+    /// Returns the changes of every monitor since the last call
+    pub fn take_events(&mut self) -> Vec<StateEvent> {
+        self.monitors
+            .iter_mut()
+            .flat_map(|state| state.workspaces.take_events())
+            .collect()
+    }
+
+    /// Lays the strips out on the new geometry of the monitors, after a resolution, work area or
+    /// display arrangement change.
+    ///
+    /// Monitors keep their strips by handle, or else by position from left to right. The windows
+    /// of disconnected monitors go to the workspaces of the leftmost remaining one, and new
+    /// monitors get empty workspaces.
+    pub fn update_monitors(&mut self, backend: &impl Backend, monitors: &[Monitor]) {
+        if monitors.is_empty() {
+            warn!("No monitor left, keeping the previous ones");
+            return;
+        }
+        let mut unmatched = (0..monitors.len()).collect::<Vec<_>>();
+        let mut matches = vec![None; self.monitors.len()];
+        for (state, matched) in self.monitors.iter().zip(&mut matches) {
+            if let Some(position) = unmatched
+                .iter()
+                .position(|index| monitors[*index].id == state.monitor.id)
+            {
+                *matched = Some(unmatched.remove(position));
+            }
+        }
+        for matched in &mut matches {
+            if matched.is_none() && !unmatched.is_empty() {
+                *matched = Some(unmatched.remove(0));
+            }
+        }
+
+        let mut disconnected = Vec::new();
+        let mut connected = Vec::with_capacity(monitors.len());
+        for (mut state, matched) in std::mem::take(&mut self.monitors).into_iter().zip(matches) {
+            let Some(index) = matched else {
+                warn!("Monitor {:?} disconnected", state.monitor.id);
+                disconnected.push(state);
+                continue;
+            };
+            let monitor = monitors[index];
+            if monitor != state.monitor {
+                info!("Monitor changed: {monitor:#?}");
+                state.monitor = monitor;
+                for tiler in state.workspaces.tilers_mut() {
+                    tiler.set_monitor(&monitor);
+                }
+            }
+            connected.push(state);
+        }
+        for index in unmatched {
+            let monitor = monitors[index];
+            info!("Monitor connected: {monitor:#?}");
+            let mut tiler = self.empty_tiler.clone();
+            tiler.set_monitor(&monitor);
+            let names = self
+                .workspace_names
+                .iter()
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            connected.push(MonitorState {
+                monitor,
+                workspaces: Workspaces::new(&names, &tiler),
+            });
+        }
+        connected.sort_by_key(|state| (state.monitor.rect.x, state.monitor.rect.y));
+
+        let leftmost = &mut connected[0].workspaces;
+        for state in disconnected {
+            leftmost.append(backend, state.workspaces);
+        }
+        self.monitors = connected;
+    }
+
+    /// Makes every window visible again and brings the ones scrolled off screen back inside of
+    /// it, so that none stay out of reach once winri exits
+    pub fn show_all(&self, backend: &impl Backend) {
+        for state in &self.monitors {
+            state.workspaces.show_all(backend);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::{
+            Rectangle,
+            fake::{FakeBackend, sync, tiler_on},
+        },
+        rule::TitlePattern,
+        tiler::{InsertPosition, ScrollPolicy},
+    };
+
+    fn backend() -> FakeBackend {
+        FakeBackend::with_monitors(&[
+            Rectangle {
+                x: 0,
+                y: 0,
+                width: 1000,
+                height: 500,
+            },
+            Rectangle {
+                x: 1000,
+                y: 0,
+                width: 1000,
+                height: 500,
+            },
+        ])
+    }
+
+    fn desktop(backend: &FakeBackend) -> Desktop {
+        desktop_with_rules(backend, Vec::new())
+    }
+
+    fn desktop_with_rules(backend: &FakeBackend, rules: Vec<Rule>) -> Desktop {
+        Desktop::new(backend.monitors().unwrap(), &["1", "2"], rules, |monitor| {
+            tiler_on(monitor, ScrollPolicy::Minimal, InsertPosition::End)
+        })
+    }
+
+    #[test]
+    fn windows_are_tiled_on_the_monitor_they_opened_on() {
+        let backend = backend();
+        let mut desktop = desktop(&backend);
+
+        let left = backend.open_on("left", 0);
+        sync(&mut desktop, &backend);
+        let right = backend.open_on("right", 1);
+        sync(&mut desktop, &backend);
+
+        assert_eq!(backend.rect(left).x, 10);
+        assert_eq!(backend.rect(right).x, 1010);
+    }
+
+    #[test]
+    fn focused_window_moves_to_the_next_monitor() {
+        let backend = backend();
+        let mut desktop = desktop(&backend);
+        let left = backend.open_on("left", 0);
+        sync(&mut desktop, &backend);
+        let right = backend.open_on("right", 1);
+        sync(&mut desktop, &backend);
+
+        desktop.focus_monitor_left(&backend);
+        assert_eq!(backend.focused_window(), Some(left));
+
+        desktop.move_focused_window_to_monitor_right(&backend);
+        sync(&mut desktop, &backend);
+        // The strip of the right monitor scrolls to show the moved window
+        assert_eq!(backend.rect(right).x, 970);
+        assert_eq!(backend.rect(left).x, 1490);
+    }
+
+    #[test]
+    fn rules_apply_on_open_and_on_title_change() {
+        let backend = backend();
+        let mut desktop = desktop_with_rules(
+            &backend,
+            vec![
+                Rule {
+                    process: Some("chat".to_string()),
+                    workspace: Some(2),
+                    ..Default::default()
+                },
+                Rule {
+                    process: Some("player".to_string()),
+                    width: Some(1.0),
+                    ..Default::default()
+                },
+                Rule {
+                    process: Some("launcher".to_string()),
+                    ignore: Some(true),
+                    ..Default::default()
+                },
+            ],
+        );
+
+        let chat = backend.open("chat");
+        let launcher = backend.open("launcher");
+        let player = backend.open("player");
+        sync(&mut desktop, &backend);
+        assert!(!backend.is_visible(chat));
+        assert_eq!(backend.rect(launcher), Rectangle::default());
+        assert_eq!(backend.rect(player).width, 1000);
+
+        desktop.rules.push(Rule {
+            title: Some(TitlePattern::new("^Mini").unwrap()),
+            float: Some(true),
+            ..Default::default()
+        });
+        backend.set_title(player, "Mini player");
+        sync(&mut desktop, &backend);
+        let tiler = desktop.monitors[0].workspaces.active_tiler();
+        assert!(tiler.contains(player));
+        assert_eq!(tiler.windows_positions(), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn restored_session_keeps_the_order_and_widths() {
+        let backend = backend();
+        let mut desktop = desktop(&backend);
+        let editor = backend.open("editor");
+        sync(&mut desktop, &backend);
+        let palette = backend.open("palette");
+        sync(&mut desktop, &backend);
+        desktop.active_tiler_mut(&backend).toggle_floating(&backend);
+        let terminal = backend.open("terminal");
+        sync(&mut desktop, &backend);
+        let tiler = desktop.active_tiler_mut(&backend);
+        tiler.swap_current_left(&backend);
+        tiler.set_width(&backend, 0.75);
+        sync(&mut desktop, &backend);
+        let session = desktop.session(&backend);
+
+        let mut desktop = desktop_with_rules(&backend, Vec::new());
+        backend.set_title(editor, "renamed");
+        desktop.restore_session(&backend, &session, &backend.opened_windows().unwrap());
+        sync(&mut desktop, &backend);
+
+        let tiler = desktop.active_tiler(&backend);
+        assert_eq!(
+            tiler.windows().collect::<Vec<_>>(),
+            [terminal, editor, palette]
+        );
+        assert_eq!(tiler.floating_windows(), [palette]);
+        assert_eq!(tiler.columns()[0].width(), 0.75);
+        assert_eq!(desktop.session(&backend).monitors[0], {
+            let mut monitor = session.monitors[0].clone();
+            monitor.workspaces[0].columns[1].windows[0].title = Some("renamed".to_string());
+            monitor
+        });
+    }
+
+    #[test]
+    fn strips_follow_the_monitor_geometry() {
+        let backend = backend();
+        let mut desktop = desktop(&backend);
+        let window = backend.open("window");
+        sync(&mut desktop, &backend);
+        assert_eq!(backend.rect(window).height, 480);
+
+        // A taskbar showing up at the bottom of the first monitor
+        let mut monitors = backend.monitors().unwrap();
+        monitors[0].work_area.height = 460;
+        desktop.update_monitors(&backend, &monitors);
+        sync(&mut desktop, &backend);
+        assert_eq!(
+            backend.rect(window),
+            Rectangle {
+                x: 10,
+                y: 10,
+                width: 500,
+                height: 440,
+            }
+        );
+
+        // Columns keep their proportion of the screen width
+        monitors[0].rect.width = 2000;
+        monitors[0].work_area.width = 2000;
+        desktop.update_monitors(&backend, &monitors);
+        sync(&mut desktop, &backend);
+        assert_eq!(backend.rect(window).width, 1000);
+    }
+
+    #[test]
+    fn windows_of_disconnected_monitors_move_to_the_remaining_one() {
+        let backend = backend();
+        let mut desktop = desktop(&backend);
+        let left = backend.open_on("left", 0);
+        sync(&mut desktop, &backend);
+        let right = backend.open_on("right", 1);
+        sync(&mut desktop, &backend);
+        desktop.monitors[1].workspaces.switch_to(&backend, 1);
+        let hidden = backend.open_on("hidden", 1);
+        sync(&mut desktop, &backend);
+
+        let monitors = backend.monitors().unwrap();
+        desktop.update_monitors(&backend, &monitors[..1]);
+        sync(&mut desktop, &backend);
+        assert_eq!(desktop.monitors.len(), 1);
+        let workspaces = &desktop.monitors[0].workspaces;
+        assert_eq!(
+            workspaces.active_tiler().windows().collect::<Vec<_>>(),
+            [left, right]
+        );
+        assert_eq!(backend.rect(right).x, 530);
+        assert!(backend.is_visible(right));
+        assert!(workspaces.tilers().nth(1).unwrap().contains(hidden));
+        assert!(!backend.is_visible(hidden));
+
+        // Connected again, the monitor starts empty and tiles the windows opening on it
+        desktop.update_monitors(&backend, &monitors);
+        let new = backend.open_on("new", 1);
+        sync(&mut desktop, &backend);
+        assert_eq!(desktop.monitors.len(), 2);
+        assert_eq!(
+            desktop.monitors[1]
+                .workspaces
+                .active_tiler()
+                .windows()
+                .collect::<Vec<_>>(),
+            [new]
+        );
+        assert_eq!(backend.rect(new).x, 1010);
+    }
+
+    #[test]
+    fn destroyed_windows_leave_without_a_snapshot() {
+        let backend = backend();
+        let mut desktop = desktop(&backend);
+        let first = backend.open("first");
+        let second = backend.open("second");
+        sync(&mut desktop, &backend);
+
+        let event = |kind, window| WindowEvent { kind, window };
+        assert!(!desktop.handle_window_event(&backend, &event(WindowEventKind::Created, second)));
+
+        backend.close(first);
+        assert!(desktop.handle_window_event(&backend, &event(WindowEventKind::Destroyed, first)));
+        assert_eq!(
+            desktop.active_tiler(&backend).windows().collect::<Vec<_>>(),
+            [second]
+        );
+        assert_eq!(backend.rect(second).x, 10);
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::backend::WindowId;
+
+/// Change of the state of winri, streamed to IPC subscribers as `{"event": "focus-changed", ...}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum StateEvent {
+    WindowAdded {
+        window: WindowId,
+        floating: bool,
+    },
+    WindowRemoved {
+        window: WindowId,
+    },
+    /// The focused window moved to another window of the active strip
+    FocusChanged {
+        window: WindowId,
+    },
+    /// Column indices within the strip
+    ColumnMoved {
+        from: usize,
+        to: usize,
+    },
+    /// Windows were moved within or between columns, which are listed from left to right with
+    /// their windows from top to bottom
+    ColumnsChanged {
+        columns: Vec<Vec<WindowId>>,
+    },
+    /// Proportion of the screen width of the column at `column`
+    WidthChanged {
+        column: usize,
+        width: f32,
+    },
+    ScrollOffsetChanged {
+        offset: i32,
+    },
+    WorkspaceChanged {
+        workspace: String,
+    },
+}
@@ -0,0 +1,50 @@
+use std::{
+    sync::{
+        Mutex,
+        mpsc::{Receiver, Sender},
+    },
+    thread,
+};
+
+use anyhow::ensure;
+use windows::{
+    Win32::System::Console::{
+        CTRL_BREAK_EVENT, CTRL_C_EVENT, CTRL_CLOSE_EVENT, SetConsoleCtrlHandler,
+    },
+    core::BOOL,
+};
+
+static CONSOLE_HOOK_CHANNEL: Mutex<Option<Sender<()>>> = Mutex::new(None);
+
+/// Runs on a thread of its own, the process ending as soon as it returns for the console closing
+unsafe extern "system" fn handler(ctrl_type: u32) -> BOOL {
+    if ![CTRL_C_EVENT, CTRL_BREAK_EVENT, CTRL_CLOSE_EVENT].contains(&ctrl_type) {
+        return false.into();
+    }
+    if let Some(sender) = CONSOLE_HOOK_CHANNEL.lock().unwrap().as_ref() {
+        // The event loop may be gone already
+        let _ = sender.send(());
+    }
+    if ctrl_type == CTRL_CLOSE_EVENT {
+        // Waits for the event loop to put the windows back and exit the process, which the system
+        // does itself after a few seconds
+        loop {
+            thread::park();
+        }
+    }
+    true.into()
+}
+
+/// Notifies Ctrl+C, Ctrl+Break and the console window closing, instead of the process being
+/// ended with the windows left off screen
+pub fn launch_hook() -> anyhow::Result<Receiver<()>> {
+    let mut console_hook_channel = CONSOLE_HOOK_CHANNEL.lock().unwrap();
+    ensure!(console_hook_channel.is_none(), "Hook already launched");
+    let (sender, receiver) = std::sync::mpsc::channel();
+    *console_hook_channel = Some(sender);
+    drop(console_hook_channel);
+    unsafe {
+        SetConsoleCtrlHandler(Some(handler), true)?;
+    }
+    Ok(receiver)
+}
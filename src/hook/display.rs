@@ -0,0 +1,96 @@
+use std::{
+    sync::{
+        Mutex,
+        mpsc::{Receiver, Sender},
+    },
+    thread,
+};
+
+use anyhow::ensure;
+use log::error;
+use windows::{
+    Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        System::LibraryLoader::GetModuleHandleW,
+        UI::WindowsAndMessaging::{
+            CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, MSG, RegisterClassW,
+            SPI_SETWORKAREA, WINDOW_EX_STYLE, WM_DISPLAYCHANGE, WM_DPICHANGED, WM_SETTINGCHANGE,
+            WNDCLASSW, WS_POPUP,
+        },
+    },
+    core::w,
+};
+
+static DISPLAY_HOOK_CHANNEL: Mutex<Option<Sender<()>>> = Mutex::new(None);
+
+unsafe extern "system" fn window_procedure(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    // The taskbar moving or resizing changes the work area without changing the resolution
+    let work_area_changed = message == WM_SETTINGCHANGE && wparam.0 == SPI_SETWORKAREA.0 as usize;
+    if (message == WM_DISPLAYCHANGE || message == WM_DPICHANGED || work_area_changed)
+        && let Some(sender) = DISPLAY_HOOK_CHANNEL.lock().unwrap().as_ref()
+    {
+        sender.send(()).unwrap();
+    }
+    unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+}
+
+/// Notifies resolution, scaling, display arrangement and work area changes.
+///
+/// These are only broadcast to top-level windows, so a hidden one is created to receive them.
+pub fn launch_hook() -> anyhow::Result<Receiver<()>> {
+    let mut display_hook_channel = DISPLAY_HOOK_CHANNEL.lock().unwrap();
+    ensure!(display_hook_channel.is_none(), "Hook already launched");
+    let (sender, receiver) = std::sync::mpsc::channel();
+    *display_hook_channel = Some(sender);
+    drop(display_hook_channel);
+    thread::spawn(|| unsafe {
+        let instance = match GetModuleHandleW(None) {
+            Ok(instance) => instance,
+            Err(err) => {
+                error!("Failed to get the module handle of winri: {err}");
+                DISPLAY_HOOK_CHANNEL.lock().unwrap().take();
+                return;
+            }
+        };
+        let class_name = w!("winri-display-listener");
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(window_procedure),
+            hInstance: instance.into(),
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        RegisterClassW(&raw const class);
+        // The window is never shown, a popup without size has no frame to draw
+        let window = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            None,
+            WS_POPUP,
+            0,
+            0,
+            0,
+            0,
+            None,
+            None,
+            Some(instance.into()),
+            None,
+        );
+        if let Err(err) = window {
+            error!("Failed to create the window listening to display changes: {err}");
+            DISPLAY_HOOK_CHANNEL.lock().unwrap().take();
+            return;
+        }
+
+        let mut message = MSG::default();
+        while GetMessageW(&raw mut message, None, 0, 0).as_bool() {
+            DispatchMessageW(&raw const message);
+        }
+        DISPLAY_HOOK_CHANNEL.lock().unwrap().take();
+    });
+    Ok(receiver)
+}
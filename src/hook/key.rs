@@ -1,10 +1,16 @@
-use std::sync::mpsc::Receiver;
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+    mpsc::Receiver,
+};
 
 use bitflags::bitflags;
-use rdev::Key;
+use rdev::{Button, Key};
+
+use super::mouse;
 
 bitflags! {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Modifiers: u8 {
         const SHIFT = 1 << 0;
         const CTRL = 1 << 1;
@@ -15,12 +21,53 @@ bitflags! {
 
 pub struct Event(pub Modifiers, pub Key);
 
-pub fn launch_hook() -> Receiver<Event> {
+/// Forwards every key press, swallowing the ones with the Windows key, or all of them while
+/// `capture` is set. Wheel and left button events made with the Windows key are swallowed and
+/// forwarded too, as well as the pointer moves if `report_moves` is set.
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "the cursor position is a whole number of pixels"
+)]
+pub fn launch_hook(
+    capture: Arc<AtomicBool>,
+    report_moves: bool,
+) -> (Receiver<Event>, Receiver<mouse::Event>) {
     let (sender, receiver) = std::sync::mpsc::channel();
+    let (mouse_sender, mouse_receiver) = std::sync::mpsc::channel();
     std::thread::spawn(move || {
         let mut modifiers = Modifiers::empty();
+        let mut cursor = (0, 0);
+        // Set between a press and a release of the left button with the Windows key
+        let mut dragging = false;
         rdev::_grab(move |event| {
             match event.event_type {
+                rdev::EventType::MouseMove { x, y } => {
+                    cursor = (x as i32, y as i32);
+                    if report_moves && !dragging {
+                        let (x, y) = cursor;
+                        mouse_sender.send(mouse::Event::Move { x, y }).unwrap();
+                    }
+                }
+                rdev::EventType::Wheel { delta_y, .. }
+                    if modifiers.contains(Modifiers::WIN) && delta_y != 0 =>
+                {
+                    mouse_sender.send(mouse::Event::Wheel(delta_y)).unwrap();
+                    return None;
+                }
+                rdev::EventType::ButtonPress(Button::Left)
+                    if modifiers.contains(Modifiers::WIN) =>
+                {
+                    dragging = true;
+                    let (x, y) = cursor;
+                    mouse_sender.send(mouse::Event::Press { x, y }).unwrap();
+                    return None;
+                }
+                rdev::EventType::ButtonRelease(Button::Left) if dragging => {
+                    dragging = false;
+                    let (x, y) = cursor;
+                    mouse_sender.send(mouse::Event::Release { x, y }).unwrap();
+                    return None;
+                }
                 rdev::EventType::KeyPress(key) => match key {
                     rdev::Key::ShiftLeft | rdev::Key::ShiftRight => {
                         modifiers.insert(Modifiers::SHIFT);
@@ -34,7 +81,9 @@ pub fn launch_hook() -> Receiver<Event> {
                     }
                     key => {
                         sender.send(Event(modifiers, key)).unwrap();
-                        return (!modifiers.contains(Modifiers::WIN)).then_some(event);
+                        let swallow =
+                            modifiers.contains(Modifiers::WIN) || capture.load(Ordering::Relaxed);
+                        return (!swallow).then_some(event);
                     }
                 },
                 rdev::EventType::KeyRelease(key) => match key {
@@ -56,5 +105,5 @@ pub fn launch_hook() -> Receiver<Event> {
         })
         .unwrap();
     });
-    receiver
+    (receiver, mouse_receiver)
 }
@@ -1,25 +1,90 @@
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
 
+use crate::{backend::WindowId, event::StateEvent, ipc};
+
+#[cfg(windows)]
+pub mod console;
+#[cfg(windows)]
+pub mod display;
 pub mod key;
+pub mod mouse;
+#[cfg(windows)]
 pub mod window;
 
+/// What happened to a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowEventKind {
+    Created,
+    Destroyed,
+    Shown,
+    Hidden,
+    Focused,
+    Minimized,
+    Restored,
+    /// The user started dragging or resizing the window
+    MoveSizeStarted,
+    MoveSizeEnded,
+    TitleChanged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowEvent {
+    pub kind: WindowEventKind,
+    pub window: WindowId,
+}
+
 pub enum Event {
     Key(key::Event),
-    Window,
+    Mouse(mouse::Event),
+    Window(WindowEvent),
+    /// Resolution, scaling, display arrangement or work area change
+    Display,
+    Ipc(ipc::Command),
+    /// IPC client waiting for the state events
+    Subscribe(Sender<StateEvent>),
+    /// Ctrl+C or the console closing, winri putting the windows back before exiting
+    Exit,
 }
 
-pub fn launch_hooks() -> anyhow::Result<Receiver<Event>> {
+/// `capture_keys` makes the key hook swallow every key instead of only the ones with the
+/// Windows key, and `report_moves` forwards every pointer move
+#[cfg(windows)]
+pub fn launch_hooks(
+    sender: Sender<Event>,
+    capture_keys: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    report_moves: bool,
+) -> anyhow::Result<()> {
     let window_event_receiver = window::launch_hook()?;
-    let key_event_receiver = key::launch_hook();
-
-    let (sender, receiver) = std::sync::mpsc::channel();
+    let display_event_receiver = display::launch_hook()?;
+    let console_event_receiver = console::launch_hook()?;
+    let (key_event_receiver, mouse_event_receiver) = key::launch_hook(capture_keys, report_moves);
 
     let window_event_sender = sender.clone();
-    let key_event_sender = sender;
+    let display_event_sender = sender.clone();
+    let console_event_sender = sender.clone();
+    let key_event_sender = sender.clone();
+    let mouse_event_sender = sender;
+
+    std::thread::spawn(move || {
+        for window_event in window_event_receiver {
+            window_event_sender
+                .send(Event::Window(window_event))
+                .unwrap();
+        }
+    });
+
+    std::thread::spawn(move || {
+        for () in display_event_receiver {
+            display_event_sender.send(Event::Display).unwrap();
+        }
+    });
 
     std::thread::spawn(move || {
-        for () in window_event_receiver {
-            window_event_sender.send(Event::Window).unwrap();
+        for () in console_event_receiver {
+            // The event loop may have exited on an earlier signal
+            if console_event_sender.send(Event::Exit).is_err() {
+                break;
+            }
         }
     });
 
@@ -29,5 +94,11 @@ pub fn launch_hooks() -> anyhow::Result<Receiver<Event>> {
         }
     });
 
-    Ok(receiver)
+    std::thread::spawn(move || {
+        for mouse_event in mouse_event_receiver {
+            mouse_event_sender.send(Event::Mouse(mouse_event)).unwrap();
+        }
+    });
+
+    Ok(())
 }
@@ -0,0 +1,12 @@
+/// Mouse input made while the Windows key is held, positions being in screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// Pointer moved, reported whatever the modifiers when focus follows the mouse
+    Move { x: i32, y: i32 },
+    /// Vertical wheel rotation, positive when scrolling up
+    Wheel(i64),
+    /// Left button pressed, starting a drag
+    Press { x: i32, y: i32 },
+    /// Left button released after a press
+    Release { x: i32, y: i32 },
+}
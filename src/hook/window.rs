@@ -5,7 +5,6 @@ use std::{
         mpsc::{Receiver, Sender},
     },
     thread,
-    time::{Duration, Instant},
 };
 
 use anyhow::ensure;
@@ -14,68 +13,76 @@ use windows::Win32::{
     UI::{
         Accessibility::{HWINEVENTHOOK, SetWinEventHook, UnhookWinEvent},
         WindowsAndMessaging::{
-            EVENT_OBJECT_CREATE, EVENT_OBJECT_FOCUS, GetMessageA, WINEVENT_OUTOFCONTEXT,
+            CHILDID_SELF, EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY, EVENT_OBJECT_FOCUS,
+            EVENT_OBJECT_HIDE, EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW, EVENT_SYSTEM_FOREGROUND,
+            EVENT_SYSTEM_MINIMIZEEND, EVENT_SYSTEM_MINIMIZESTART, EVENT_SYSTEM_MOVESIZEEND,
+            EVENT_SYSTEM_MOVESIZESTART, GetMessageA, OBJID_WINDOW, WINEVENT_OUTOFCONTEXT,
             WINEVENT_SKIPOWNPROCESS,
         },
     },
 };
 
-const WINDOW_HOOK_COOLDOWN: Duration = Duration::from_millis(200);
-
-struct WindowHookContext {
-    notifier: Sender<()>,
-    last_time_notified: Instant,
-}
+use crate::{
+    backend::WindowId,
+    hook::{WindowEvent, WindowEventKind},
+};
 
-impl WindowHookContext {
-    fn new(notifier: Sender<()>) -> Self {
-        Self {
-            notifier,
-            last_time_notified: Instant::now(),
-        }
-    }
+static WINDOW_HOOK_CHANNEL: Mutex<Option<Sender<WindowEvent>>> = Mutex::new(None);
 
-    fn tick(&mut self) {
-        let elapsed = self.last_time_notified.elapsed();
-        if elapsed > WINDOW_HOOK_COOLDOWN {
-            self.notifier.send(()).unwrap();
-            self.last_time_notified = Instant::now();
-        } else {
-            let original_last_time_notified = self.last_time_notified;
-            thread::spawn(move || {
-                thread::sleep(WINDOW_HOOK_COOLDOWN - elapsed);
-                if let Some(context) = WINDOW_HOOK_CHANNEL.lock().unwrap().as_mut()
-                    && context.last_time_notified == original_last_time_notified
-                {
-                    context.tick();
-                }
-            });
-        }
+const fn event_kind(event: u32) -> Option<WindowEventKind> {
+    match event {
+        EVENT_OBJECT_CREATE => Some(WindowEventKind::Created),
+        EVENT_OBJECT_DESTROY => Some(WindowEventKind::Destroyed),
+        EVENT_OBJECT_SHOW => Some(WindowEventKind::Shown),
+        EVENT_OBJECT_HIDE => Some(WindowEventKind::Hidden),
+        EVENT_OBJECT_FOCUS | EVENT_SYSTEM_FOREGROUND => Some(WindowEventKind::Focused),
+        EVENT_SYSTEM_MINIMIZESTART => Some(WindowEventKind::Minimized),
+        EVENT_SYSTEM_MINIMIZEEND => Some(WindowEventKind::Restored),
+        EVENT_SYSTEM_MOVESIZESTART => Some(WindowEventKind::MoveSizeStarted),
+        EVENT_SYSTEM_MOVESIZEEND => Some(WindowEventKind::MoveSizeEnded),
+        EVENT_OBJECT_NAMECHANGE => Some(WindowEventKind::TitleChanged),
+        _ => None,
     }
 }
 
-static WINDOW_HOOK_CHANNEL: Mutex<Option<WindowHookContext>> = Mutex::new(None);
-
 unsafe extern "system" fn hook_callback(
     _hwineventhook: HWINEVENTHOOK,
-    _event: u32,
-    _hwnd: HWND,
-    _idobject: i32,
-    _idchild: i32,
+    event: u32,
+    hwnd: HWND,
+    idobject: i32,
+    idchild: i32,
     _ideventthread: u32,
     _dwmseventtime: u32,
 ) {
-    if let Some(context) = WINDOW_HOOK_CHANNEL.lock().unwrap().as_mut() {
-        context.tick();
+    let Some(kind) = event_kind(event) else {
+        return;
+    };
+    // Events of the controls inside windows, such as their names changing, are not about the
+    // windows themselves. Focus events are the exception, the focus going to a control.
+    #[allow(
+        clippy::cast_possible_wrap,
+        reason = "CHILDID_SELF is 0, the same value once signed"
+    )]
+    let is_window_event = idobject == OBJID_WINDOW.0 && idchild == CHILDID_SELF as i32;
+    if (is_window_event || event == EVENT_OBJECT_FOCUS)
+        && let Some(sender) = WINDOW_HOOK_CHANNEL.lock().unwrap().as_ref()
+    {
+        sender
+            .send(WindowEvent {
+                kind,
+                window: WindowId(hwnd.0 as isize),
+            })
+            .unwrap();
     }
 }
 
-pub fn launch_hook() -> anyhow::Result<Receiver<()>> {
-    let mut window_hook_context = WINDOW_HOOK_CHANNEL.lock().unwrap();
-    ensure!(window_hook_context.is_none(), "Hook already launched");
+/// Reports what happens to windows as it happens, bursts being coalesced by the event loop
+pub fn launch_hook() -> anyhow::Result<Receiver<WindowEvent>> {
+    let mut window_hook_channel = WINDOW_HOOK_CHANNEL.lock().unwrap();
+    ensure!(window_hook_channel.is_none(), "Hook already launched");
     let (sender, receiver) = std::sync::mpsc::channel();
-    *window_hook_context = Some(WindowHookContext::new(sender));
-    drop(window_hook_context);
+    *window_hook_channel = Some(sender);
+    drop(window_hook_channel);
     thread::spawn(|| unsafe {
         let hook = SetWinEventHook(
             EVENT_OBJECT_CREATE,
@@ -86,8 +93,29 @@ pub fn launch_hook() -> anyhow::Result<Receiver<()>> {
             0,
             WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
         );
+        // Foreground, move and resize, and minimize events
+        let system_hook = SetWinEventHook(
+            EVENT_SYSTEM_FOREGROUND,
+            EVENT_SYSTEM_MINIMIZEEND,
+            None,
+            Some(hook_callback),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        );
+        let name_change_hook = SetWinEventHook(
+            EVENT_OBJECT_NAMECHANGE,
+            EVENT_OBJECT_NAMECHANGE,
+            None,
+            Some(hook_callback),
+            0,
+            0,
+            WINEVENT_OUTOFCONTEXT | WINEVENT_SKIPOWNPROCESS,
+        );
         if !GetMessageA(null_mut(), None, 0, 0).as_bool() {
             let _ = UnhookWinEvent(hook);
+            let _ = UnhookWinEvent(system_hook);
+            let _ = UnhookWinEvent(name_change_hook);
             WINDOW_HOOK_CHANNEL.lock().unwrap().take();
         }
     });
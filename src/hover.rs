@@ -0,0 +1,63 @@
+use std::time::{Duration, Instant};
+
+use crate::backend::WindowId;
+
+/// Window the pointer rests on, to be focused once it stayed there for `delay`
+pub struct HoverFocus {
+    delay: Duration,
+    hovered: Option<(WindowId, Instant)>,
+}
+
+impl HoverFocus {
+    pub const fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            hovered: None,
+        }
+    }
+
+    /// Records the window under the pointer, the delay restarting only when it changes
+    pub fn pointer_moved(&mut self, window: Option<WindowId>, now: Instant) {
+        if self.hovered.map(|(hovered, _)| hovered) != window {
+            self.hovered = window.map(|window| (window, now));
+        }
+    }
+
+    /// Returns when the hovered window is due to be focused
+    pub fn deadline(&self) -> Option<Instant> {
+        self.hovered.map(|(_, since)| since + self.delay)
+    }
+
+    /// Returns the hovered window once the delay elapsed, forgetting it
+    pub fn take_due(&mut self, now: Instant) -> Option<WindowId> {
+        if self.deadline().is_some_and(|deadline| deadline <= now) {
+            self.hovered.take().map(|(window, _)| window)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_are_focused_after_resting_under_the_pointer() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut hover = HoverFocus::new(Duration::from_millis(100));
+
+        hover.pointer_moved(Some(WindowId(1)), at(0));
+        hover.pointer_moved(Some(WindowId(2)), at(50));
+        // Moving within the same window keeps the delay going
+        hover.pointer_moved(Some(WindowId(2)), at(120));
+        assert_eq!(hover.take_due(at(140)), None);
+        assert_eq!(hover.take_due(at(150)), Some(WindowId(2)));
+        assert_eq!(hover.take_due(at(200)), None);
+
+        hover.pointer_moved(Some(WindowId(3)), at(200));
+        hover.pointer_moved(None, at(250));
+        assert_eq!(hover.deadline(), None);
+    }
+}
@@ -0,0 +1,309 @@
+#[cfg(windows)]
+mod pipe;
+#[cfg(not(windows))]
+mod socket;
+
+#[cfg(windows)]
+use pipe as transport;
+#[cfg(not(windows))]
+use socket as transport;
+
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use anyhow::anyhow;
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::{Backend, WindowId},
+    event::StateEvent,
+    hook::Event,
+    tiler::ScrollTiler,
+};
+
+/// Name of the named pipe on Windows, and of the socket in the runtime directory elsewhere
+pub const ENDPOINT: &str = "winri";
+
+/// Newline-delimited JSON request, such as `{"action": "set-width 0.5"}`, `{"query": "strip"}`
+/// or `"subscribe"`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub enum Request {
+    /// Action in the syntax of the key bindings
+    Action(String),
+    Query(Query),
+    /// Keeps the connection open, streaming every state event as a line of JSON
+    Subscribe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Query {
+    /// Strip of the active workspace of the focused monitor
+    Strip,
+    FocusedWindow,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reply {
+    Done,
+    Strip(Strip),
+    FocusedWindow(Option<WindowInfo>),
+    Error(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowInfo {
+    pub id: isize,
+    pub title: Option<String>,
+    pub process: Option<String>,
+}
+
+impl WindowInfo {
+    fn new(backend: &impl Backend, window: WindowId) -> Self {
+        Self {
+            id: window.0,
+            title: backend.title(window).ok().flatten(),
+            process: backend.process_name(window).ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnInfo {
+    pub windows: Vec<WindowInfo>,
+    /// Proportion of the screen width
+    pub width: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Strip {
+    pub columns: Vec<ColumnInfo>,
+    pub floating: Vec<WindowInfo>,
+    pub active_column: usize,
+    pub scroll_offset: i32,
+}
+
+impl Strip {
+    pub fn new(backend: &impl Backend, tiler: &ScrollTiler) -> Self {
+        Self {
+            columns: tiler
+                .columns()
+                .iter()
+                .map(|column| ColumnInfo {
+                    windows: column
+                        .windows()
+                        .iter()
+                        .map(|window| WindowInfo::new(backend, *window))
+                        .collect(),
+                    width: column.width(),
+                })
+                .collect(),
+            floating: tiler
+                .floating_windows()
+                .iter()
+                .map(|window| WindowInfo::new(backend, *window))
+                .collect(),
+            active_column: tiler.active_column(),
+            scroll_offset: tiler.scroll_offset(),
+        }
+    }
+}
+
+/// Answers a query about the given strip
+pub fn answer(query: Query, backend: &impl Backend, tiler: &ScrollTiler) -> Reply {
+    match query {
+        Query::Strip => Reply::Strip(Strip::new(backend, tiler)),
+        Query::FocusedWindow => Reply::FocusedWindow(
+            backend
+                .focused_window()
+                .map(|window| WindowInfo::new(backend, window)),
+        ),
+    }
+}
+
+/// Request received by the server, to be answered from the event loop
+pub struct Command {
+    pub request: Request,
+    reply: Sender<Reply>,
+}
+
+impl Command {
+    pub fn reply(self, reply: Reply) {
+        // The client may have gone away in the meantime
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// Clients streaming the state events
+#[derive(Default)]
+pub struct Subscribers(Vec<Sender<StateEvent>>);
+
+impl Subscribers {
+    pub fn add(&mut self, subscriber: Sender<StateEvent>) {
+        self.0.push(subscriber);
+    }
+
+    /// Forwards `events` to every subscriber, forgetting the ones that went away
+    pub fn broadcast(&mut self, events: &[StateEvent]) {
+        if events.is_empty() {
+            return;
+        }
+        self.0.retain(|subscriber| {
+            events
+                .iter()
+                .all(|event| subscriber.send(event.clone()).is_ok())
+        });
+    }
+}
+
+/// Listens on `name`, forwarding every request to `events`
+pub fn launch_server(name: &str, events: Sender<Event>) -> anyhow::Result<()> {
+    let mut listener = transport::Listener::bind(name)?;
+    thread::spawn(move || {
+        loop {
+            match listener.accept() {
+                Ok(stream) => {
+                    let events = events.clone();
+                    thread::spawn(move || {
+                        if let Err(err) = handle_client(stream, &events) {
+                            warn!("IPC client failed: {err:#}");
+                        }
+                    });
+                }
+                Err(err) => {
+                    error!("IPC server stopped: {err:#}");
+                    break;
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handle_client(mut stream: impl Read + Write, events: &Sender<Event>) -> anyhow::Result<()> {
+    let mut line = String::new();
+    BufReader::new(&mut stream).read_line(&mut line)?;
+
+    let reply = match serde_json::from_str::<Request>(&line) {
+        Ok(Request::Subscribe) => {
+            let (sender, receiver) = mpsc::channel();
+            events
+                .send(Event::Subscribe(sender))
+                .map_err(|_| anyhow!("winri is shutting down"))?;
+            // Writing fails once the client disconnects, which ends the subscription
+            for event in receiver {
+                writeln!(stream, "{}", serde_json::to_string(&event)?)?;
+            }
+            return Ok(());
+        }
+        Ok(request) => {
+            let (sender, receiver) = mpsc::channel();
+            events
+                .send(Event::Ipc(Command {
+                    request,
+                    reply: sender,
+                }))
+                .map_err(|_| anyhow!("winri is shutting down"))?;
+            receiver
+                .recv()
+                .unwrap_or_else(|_| Reply::Error("winri dropped the request".to_string()))
+        }
+        Err(err) => Reply::Error(format!("Invalid request: {err}")),
+    };
+    writeln!(stream, "{}", serde_json::to_string(&reply)?)?;
+    Ok(())
+}
+
+/// Sends `request` to the server listening on `name` and waits for its reply
+pub fn send(name: &str, request: &Request) -> anyhow::Result<Reply> {
+    let mut stream = transport::connect(name)?;
+    writeln!(stream, "{}", serde_json::to_string(request)?)?;
+    let mut line = String::new();
+    BufReader::new(&mut stream).read_line(&mut line)?;
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Subscribes to the server listening on `name`, returning its events as they come
+pub fn subscribe(name: &str) -> anyhow::Result<impl Iterator<Item = anyhow::Result<StateEvent>>> {
+    let mut stream = transport::connect(name)?;
+    writeln!(stream, "{}", serde_json::to_string(&Request::Subscribe)?)?;
+    Ok(BufReader::new(stream)
+        .lines()
+        .map(|line| Ok(serde_json::from_str(&line?)?)))
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_use_a_compact_json_form() {
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"action": "set-width 0.5"}"#).unwrap(),
+            Request::Action("set-width 0.5".to_string())
+        );
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"query": "focused-window"}"#).unwrap(),
+            Request::Query(Query::FocusedWindow)
+        );
+        assert_eq!(serde_json::to_string(&Reply::Done).unwrap(), r#""done""#);
+    }
+
+    #[test]
+    fn requests_are_answered_from_the_event_loop() {
+        let name = format!("winri-test-{}", std::process::id());
+        let (sender, events) = mpsc::channel();
+        launch_server(&name, sender).unwrap();
+        thread::spawn(move || {
+            let mut subscribers = Subscribers::default();
+            for event in events {
+                match event {
+                    Event::Ipc(command) => {
+                        let reply = match &command.request {
+                            Request::Action(action) => Reply::Error(format!("no {action}")),
+                            Request::Query(_) => Reply::Done,
+                            Request::Subscribe => unreachable!(),
+                        };
+                        command.reply(reply);
+                    }
+                    Event::Subscribe(subscriber) => {
+                        subscribers.add(subscriber);
+                        subscribers.broadcast(&[
+                            StateEvent::WorkspaceChanged {
+                                workspace: "2".to_string(),
+                            },
+                            StateEvent::ScrollOffsetChanged { offset: 40 },
+                        ]);
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        assert_eq!(
+            send(&name, &Request::Query(Query::Strip)).unwrap(),
+            Reply::Done
+        );
+        assert_eq!(
+            send(&name, &Request::Action("focus-left".to_string())).unwrap(),
+            Reply::Error("no focus-left".to_string())
+        );
+
+        let events = subscribe(&name).unwrap();
+        assert_eq!(
+            events.take(2).map(Result::unwrap).collect::<Vec<_>>(),
+            [
+                StateEvent::WorkspaceChanged {
+                    workspace: "2".to_string()
+                },
+                StateEvent::ScrollOffsetChanged { offset: 40 },
+            ]
+        );
+    }
+}
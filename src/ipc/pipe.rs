@@ -0,0 +1,152 @@
+use std::io::{self, Read, Write};
+
+use anyhow::{Context, anyhow};
+use windows::{
+    Win32::{
+        Foundation::{
+            CloseHandle, ERROR_ACCESS_DENIED, ERROR_PIPE_CONNECTED, GENERIC_READ, GENERIC_WRITE,
+            GetLastError, HANDLE,
+        },
+        Storage::FileSystem::{
+            CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_FLAG_FIRST_PIPE_INSTANCE,
+            FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_NONE, FlushFileBuffers, OPEN_EXISTING,
+            PIPE_ACCESS_DUPLEX, ReadFile, WriteFile,
+        },
+        System::Pipes::{
+            ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_READMODE_BYTE,
+            PIPE_TYPE_BYTE, PIPE_UNLIMITED_INSTANCES, PIPE_WAIT,
+        },
+    },
+    core::HSTRING,
+};
+
+use crate::{utils::winapi::last_error, wincall};
+
+const BUFFER_SIZE: u32 = 4096;
+
+fn path(name: &str) -> HSTRING {
+    HSTRING::from(format!(r"\\.\pipe\{name}"))
+}
+
+/// One end of a named pipe, the server end being flushed and disconnected once dropped
+pub struct Stream {
+    handle: HANDLE,
+    server: bool,
+}
+
+// A pipe handle can be used from any thread
+unsafe impl Send for Stream {}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut read = 0;
+        wincall!(ReadFile(self.handle, Some(buf), Some(&raw mut read), None))?;
+        Ok(read as usize)
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = 0;
+        wincall!(WriteFile(
+            self.handle,
+            Some(buf),
+            Some(&raw mut written),
+            None
+        ))?;
+        Ok(written as usize)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        if self.server {
+            // Closing the pipe without flushing it would discard what the client did not read yet
+            let _ = wincall!(FlushFileBuffers(self.handle));
+            let _ = wincall!(DisconnectNamedPipe(self.handle));
+        }
+        let _ = wincall!(CloseHandle(self.handle));
+    }
+}
+
+/// Named pipe, one instance being created ahead for the next client
+pub struct Listener {
+    name: HSTRING,
+    next: HANDLE,
+}
+
+// The pending pipe instance is only used by the thread owning the listener
+unsafe impl Send for Listener {}
+
+impl Listener {
+    pub fn bind(name: &str) -> anyhow::Result<Self> {
+        let name = path(name);
+        let next = create_instance(&name, true)?;
+        Ok(Self { name, next })
+    }
+
+    pub fn accept(&mut self) -> anyhow::Result<Stream> {
+        let handle = self.next;
+        if let Err(err) = wincall!(ConnectNamedPipe(handle, None))
+            && err.code() != ERROR_PIPE_CONNECTED.to_hresult()
+        {
+            return Err(err).context("Failed to accept a pipe client");
+        }
+        self.next = create_instance(&self.name, false)?;
+        Ok(Stream {
+            handle,
+            server: true,
+        })
+    }
+}
+
+/// Creates an instance of the pipe, the `first` one failing when another process already owns
+/// the name instead of sharing its clients with it
+fn create_instance(name: &HSTRING, first: bool) -> anyhow::Result<HANDLE> {
+    let first_instance = if first {
+        FILE_FLAG_FIRST_PIPE_INSTANCE
+    } else {
+        FILE_FLAGS_AND_ATTRIBUTES(0)
+    };
+    let handle = wincall!(CreateNamedPipeW(
+        name,
+        PIPE_ACCESS_DUPLEX | first_instance,
+        PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT,
+        PIPE_UNLIMITED_INSTANCES,
+        BUFFER_SIZE,
+        BUFFER_SIZE,
+        0,
+        None,
+    ));
+    if handle.is_invalid() {
+        anyhow::ensure!(
+            !first || unsafe { GetLastError() } != ERROR_ACCESS_DENIED,
+            "winri is already listening on {name}"
+        );
+        return Err(last_error().unwrap_or_else(|| anyhow!("Unknown error")))
+            .with_context(|| format!("Failed to create pipe {name}"));
+    }
+    Ok(handle)
+}
+
+pub fn connect(name: &str) -> anyhow::Result<Stream> {
+    let path = path(name);
+    let handle = wincall!(CreateFileW(
+        &path,
+        (GENERIC_READ | GENERIC_WRITE).0,
+        FILE_SHARE_NONE,
+        None,
+        OPEN_EXISTING,
+        FILE_ATTRIBUTE_NORMAL,
+        None,
+    ))
+    .with_context(|| format!("Failed to connect to {path}, is winri running?"))?;
+    Ok(Stream {
+        handle,
+        server: false,
+    })
+}
@@ -0,0 +1,47 @@
+use std::{
+    fs,
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+};
+
+use anyhow::Context;
+
+pub type Stream = UnixStream;
+
+fn path(name: &str) -> PathBuf {
+    dirs::runtime_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(format!("{name}.sock"))
+}
+
+/// Unix socket in the runtime directory
+pub struct Listener(UnixListener);
+
+impl Listener {
+    pub fn bind(name: &str) -> anyhow::Result<Self> {
+        let path = path(name);
+        if path.exists() {
+            anyhow::ensure!(
+                UnixStream::connect(&path).is_err(),
+                "winri is already listening on {}",
+                path.display()
+            );
+            // Left behind by an instance that did not exit cleanly
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove stale socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(&path)
+            .with_context(|| format!("Failed to bind socket {}", path.display()))?;
+        Ok(Self(listener))
+    }
+
+    pub fn accept(&mut self) -> anyhow::Result<Stream> {
+        Ok(self.0.accept()?.0)
+    }
+}
+
+pub fn connect(name: &str) -> anyhow::Result<Stream> {
+    let path = path(name);
+    UnixStream::connect(&path)
+        .with_context(|| format!("Failed to connect to {}, is winri running?", path.display()))
+}
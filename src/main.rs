@@ -1,33 +1,67 @@
+// Off Windows there is no backend to drive the tiler, only the tests exercise it
+#![cfg_attr(not(windows), allow(dead_code))]
+
+mod animation;
+mod backend;
+mod binding;
+mod config;
+mod debounce;
+mod desktop;
+mod event;
 mod hook;
+mod hover;
+mod ipc;
+mod overview;
+mod rule;
+#[cfg(windows)]
 mod screen;
+mod session;
 mod tiler;
 mod utils;
+#[cfg(windows)]
 mod window;
+mod workspace;
 
-use std::collections::HashSet;
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvTimeoutError},
+    },
+    time::{Duration, Instant},
+};
 
-use log::info;
-use rdev::Key;
+use anyhow::bail;
+use log::{info, warn};
 
 use crate::{
-    hook::{
-        key::{self, Modifiers},
-        launch_hooks,
-    },
-    screen::screen_size,
+    animation::{Animated, Clock},
+    backend::{Backend, WindowId},
+    binding::Action,
+    config::Config,
+    debounce::Debouncer,
+    desktop::Desktop,
+    hook::{key, mouse},
+    hover::HoverFocus,
+    ipc::{Query, Reply, Request, Subscribers},
+    overview::Overview,
+    session::Session,
     tiler::ScrollTiler,
-    window::{Window, filter::opened_windows},
 };
 
-fn get_process_names(windows: &HashSet<Window>) -> Vec<String> {
+const WORKSPACE_NAMES: &[&str] = &["1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+fn get_process_names(backend: &impl Backend, windows: &[WindowId]) -> Vec<String> {
+    let focused_window = backend.focused_window();
     windows
         .iter()
         .map(|w| {
-            let is_focused = w.is_focused().unwrap_or(false);
+            let is_focused = focused_window == Some(*w);
             format!(
                 "{}{}",
                 if is_focused { "[FOCUSED] " } else { "" },
-                w.process_name()
+                backend
+                    .process_name(*w)
                     .ok()
                     .unwrap_or_else(|| "[ERROR] Could not get process name".to_string())
             )
@@ -38,49 +72,397 @@ fn get_process_names(windows: &HashSet<Window>) -> Vec<String> {
 fn main() -> anyhow::Result<()> {
     pretty_env_logger::init();
 
-    let (screen_width, screen_height) = screen_size()?;
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.split_first() {
+        None => serve(),
+        Some((command, words)) if command == "msg" => message(words),
+        Some((command, _)) => bail!("Unknown command `{command}`, usage: winri [msg <action>]"),
+    }
+}
+
+#[cfg(windows)]
+fn serve() -> anyhow::Result<()> {
+    if let Err(err) = screen::declare_dpi_awareness() {
+        warn!("Could not declare per-monitor DPI awareness: {err:#}");
+    }
+    let config = Config::load()?;
+    let backend = Animated::new(
+        backend::win32::Win32Backend::new(window::filter::WindowFilter {
+            ignored_classes: config.ignored_classes.clone(),
+            ignored_processes: config.ignored_processes.clone(),
+        }),
+        animation::SystemClock,
+        Duration::from_millis(config.animation_duration),
+        config.animation_easing,
+    );
+    let (sender, events) = std::sync::mpsc::channel();
+    let capture_keys = std::sync::Arc::new(AtomicBool::new(false));
+    hook::launch_hooks(
+        sender.clone(),
+        capture_keys.clone(),
+        config.focus_follows_mouse,
+    )?;
+    ipc::launch_server(ipc::ENDPOINT, sender)?;
+    run(&backend, &config, events, &capture_keys)
+}
+
+#[cfg(not(windows))]
+fn serve() -> anyhow::Result<()> {
+    bail!("winri only runs on Windows")
+}
+
+/// Sends an action such as `set-width 0.5`, or a query such as `query strip`, to the running
+/// instance and prints its reply, or prints its events one per line after `subscribe`
+fn message(words: &[String]) -> anyhow::Result<()> {
+    let request = match words {
+        [] => bail!(
+            "usage: winri msg <action> | winri msg query <strip|focused-window> | winri msg subscribe"
+        ),
+        [subscribe] if subscribe == "subscribe" => {
+            for event in ipc::subscribe(ipc::ENDPOINT)? {
+                println!("{}", serde_json::to_string(&event?)?);
+            }
+            return Ok(());
+        }
+        [query, name] if query == "query" => {
+            Request::Query(serde_json::from_value::<Query>(name.as_str().into())?)
+        }
+        words => Request::Action(words.join(" ")),
+    };
+    match ipc::send(ipc::ENDPOINT, &request)? {
+        Reply::Error(err) => bail!(err),
+        reply => println!("{}", serde_json::to_string_pretty(&reply)?),
+    }
+    Ok(())
+}
+
+fn run(
+    backend: &Animated<impl Backend, impl Clock>,
+    config: &Config,
+    events: Receiver<hook::Event>,
+    capture_keys: &AtomicBool,
+) -> anyhow::Result<()> {
+    let monitors = backend.monitors()?;
+    info!("Monitors: {monitors:#?}");
+    anyhow::ensure!(!monitors.is_empty(), "No monitor found");
+
+    let mut desktop = Desktop::new(monitors, WORKSPACE_NAMES, config.rules.clone(), |monitor| {
+        ScrollTiler::new(
+            config.gaps(),
+            config.width_presets.clone(),
+            config.default_width,
+            config.scroll_policy,
+            config.insert_position,
+            monitor,
+        )
+    });
 
-    let mut tiler = ScrollTiler::new(10, screen_width, screen_height);
+    let mut overview: Option<Overview> = None;
+    // Window whose column is dragged with the mouse
+    let mut dragged: Option<WindowId> = None;
+    let mut hover = HoverFocus::new(Duration::from_millis(config.focus_follows_mouse_delay));
+    // Set by the quit action or signal, the loop ending once the event is handled
+    let mut quitting = false;
 
     macro_rules! update_tiler {
         () => {
-            let windows_snapshot = opened_windows()?;
+            let windows_snapshot = backend.opened_windows()?;
             info!(
                 "Opened windows: {:#?}",
-                get_process_names(&windows_snapshot)
+                get_process_names(backend, &windows_snapshot)
             );
-            tiler.handle_window_snapshot(&windows_snapshot);
+            desktop.handle_window_snapshot(backend, &windows_snapshot);
+            if overview.is_some() {
+                show_overview(backend, desktop.active_tiler(backend));
+            }
+        };
+    }
+
+    // Goes back to the normal layout, focused and scrolled to the column at `$column`
+    macro_rules! close_overview {
+        ($column:expr) => {
+            let column = $column;
+            overview = None;
+            capture_keys.store(false, Ordering::Relaxed);
+            desktop.set_overview(backend, false);
+            desktop.active_tiler(backend).focus_column(backend, column);
+            update_tiler!();
+        };
+    }
+
+    macro_rules! run_action {
+        ($action:expr) => {
+            match (&overview, $action) {
+                (None, Action::ToggleOverview) => {
+                    overview = Some(Overview::new(desktop.active_tiler(backend)));
+                    capture_keys.store(true, Ordering::Relaxed);
+                    desktop.set_overview(backend, true);
+                    show_overview(backend, desktop.active_tiler(backend));
+                }
+                (Some(current), Action::ToggleOverview) => {
+                    close_overview!(current.selected());
+                }
+                (_, Action::Quit) => quitting = true,
+                (_, action) => {
+                    if execute(backend, &mut desktop, action) {
+                        update_tiler!();
+                    }
+                }
+            }
         };
     }
 
+    let mut session = Session::load().unwrap_or_else(|err| {
+        warn!("Could not restore the session: {err:#}");
+        Session::default()
+    });
+    desktop.restore_session(backend, &session, &backend.opened_windows()?);
+
     update_tiler!();
 
-    let events = launch_hooks()?;
+    let mut subscribers = Subscribers::default();
+    // Enumerations of the windows asked for by window events
+    let mut snapshots = Debouncer::new(
+        animation::SystemClock,
+        Duration::from_millis(config.window_event_debounce),
+    );
+    loop {
+        // Animation frames are drawn, hovered windows focused and held back enumerations of the
+        // windows run while waiting for the next event
+        let timeout = [
+            backend.tick().then_some(animation::FRAME_INTERVAL),
+            [hover.deadline(), snapshots.deadline()]
+                .into_iter()
+                .flatten()
+                .min()
+                .map(|deadline| deadline.saturating_duration_since(Instant::now())),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        let event = if let Some(timeout) = timeout {
+            match events.recv_timeout(timeout) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            let Ok(event) = events.recv() else {
+                break;
+            };
+            Some(event)
+        };
 
-    for event in events {
+        // Every event starts a new layout pass, the focus having possibly moved since the last one
+        backend.forget_focus();
         match event {
-            hook::Event::Key(key::Event(modifiers, key)) => match key {
-                Key::LeftArrow if modifiers.contains(Modifiers::CTRL.union(Modifiers::WIN)) => {
-                    tiler.swap_current_left();
-                    update_tiler!();
+            None => {}
+            Some(hook::Event::Key(key::Event(modifiers, key))) => {
+                let action = config.bindings.get(modifiers, key);
+                if let Some(current) = &mut overview
+                    && action != Some(Action::ToggleOverview)
+                {
+                    if let Some(input) = overview::Input::from_key(key) {
+                        let tiler = desktop.active_tiler(backend);
+                        match current.handle(input, tiler.columns().len()) {
+                            Some(column) => {
+                                close_overview!(column);
+                            }
+                            None => tiler.focus_column(backend, current.selected()),
+                        }
+                    }
+                } else if let Some(action) = action {
+                    info!("Running {action:?}");
+                    run_action!(action);
                 }
-                Key::RightArrow if modifiers.contains(Modifiers::CTRL.union(Modifiers::WIN)) => {
-                    tiler.swap_current_right();
+            }
+            Some(hook::Event::Mouse(mouse::Event::Wheel(delta))) => {
+                // The strip scrolls along with the focus
+                run_action!(if delta > 0 {
+                    Action::FocusLeft
+                } else {
+                    Action::FocusRight
+                });
+            }
+            Some(hook::Event::Mouse(mouse::Event::Move { x, y })) => {
+                let window = desktop
+                    .tiler_at_mut(x, y)
+                    .and_then(|tiler| tiler.window_at(x, y))
+                    .filter(|window| Some(*window) != backend.focused_window());
+                hover.pointer_moved(window, Instant::now());
+            }
+            Some(hook::Event::Mouse(mouse::Event::Press { x, y })) => {
+                dragged = desktop
+                    .tiler_at_mut(x, y)
+                    .and_then(|tiler| tiler.window_at(x, y));
+            }
+            Some(hook::Event::Mouse(mouse::Event::Release { x, y })) => {
+                if let Some(window) = dragged.take()
+                    && let Some(tiler) = desktop.tiler_at_mut(x, y)
+                    && let Some(index) = tiler.column_index_at(x)
+                {
+                    info!("Dropping the column of window {window:?} at index {index}");
+                    tiler.move_column(window, index);
                     update_tiler!();
                 }
-                Key::LeftArrow if modifiers.contains(Modifiers::WIN) => {
-                    tiler.focus_left();
+            }
+            Some(hook::Event::Window(event)) => {
+                backend.window_changed(&event);
+                if desktop.handle_window_event(backend, &event) {
+                    if overview.is_some() {
+                        show_overview(backend, desktop.active_tiler(backend));
+                    }
+                } else if snapshots.trigger() {
+                    update_tiler!();
                 }
-                Key::RightArrow if modifiers.contains(Modifiers::WIN) => {
-                    tiler.focus_right();
+            }
+            Some(hook::Event::Display) => match backend.monitors() {
+                Ok(monitors) => {
+                    desktop.update_monitors(backend, &monitors);
+                    update_tiler!();
                 }
-                _ => {}
+                Err(err) => warn!("Failed to get the monitors: {err:#}"),
             },
-            hook::Event::Window => {
-                update_tiler!();
+            Some(hook::Event::Ipc(command)) => {
+                let reply = match &command.request {
+                    Request::Action(action) => match action.parse::<Action>() {
+                        Ok(action) => {
+                            info!("Running {action:?} from IPC");
+                            run_action!(action);
+                            Reply::Done
+                        }
+                        Err(err) => Reply::Error(format!("{err:#}")),
+                    },
+                    Request::Query(query) => {
+                        ipc::answer(*query, backend, desktop.active_tiler(backend))
+                    }
+                    Request::Subscribe => {
+                        Reply::Error("subscriptions are handled by the server".to_string())
+                    }
+                };
+                command.reply(reply);
+            }
+            Some(hook::Event::Subscribe(subscriber)) => subscribers.add(subscriber),
+            Some(hook::Event::Exit) => {
+                info!("Exiting");
+                quitting = true;
             }
         }
+        // Checked after every event as well, a steady stream of them never letting the wait time
+        // out
+        if snapshots.take_due() {
+            update_tiler!();
+        }
+        if let Some(window) = hover.take_due(Instant::now())
+            && let Some(tiler) = desktop.tiler_of_mut(window)
+        {
+            tiler.focus_under_pointer(backend, window);
+        }
+        let events = desktop.take_events();
+        if !events.is_empty() {
+            subscribers.broadcast(&events);
+            save_session(backend, &desktop, &mut session);
+        }
+        if quitting {
+            if let Some(current) = &overview {
+                close_overview!(current.selected());
+            }
+            break;
+        }
     }
 
+    save_session(backend, &desktop, &mut session);
+    desktop.show_all(backend);
+    // The windows brought back into view are animated as well, they have to get there now
+    backend.finish();
+
     Ok(())
 }
+
+/// Moves the windows of `tiler` to their place in the overview
+fn show_overview(backend: &impl Backend, tiler: &ScrollTiler) {
+    for (window, rect) in overview::layout(tiler) {
+        if let Err(err) = backend.move_window(window, rect) {
+            warn!("Failed to move window {window:?}: {err}");
+        }
+    }
+}
+
+/// Saves the session of `desktop` when it differs from the last saved one
+fn save_session(backend: &impl Backend, desktop: &Desktop, saved: &mut Session) {
+    let session = desktop.session(backend);
+    if session != *saved {
+        if let Err(err) = session.save() {
+            warn!("Failed to save the session: {err:#}");
+        }
+        *saved = session;
+    }
+}
+
+/// Runs `action`, returning whether the windows have to be laid out again
+fn execute(backend: &impl Backend, desktop: &mut Desktop, action: Action) -> bool {
+    match action {
+        Action::SwitchToWorkspace(index) => {
+            desktop
+                .active_workspaces_mut(backend)
+                .switch_to(backend, index);
+        }
+        Action::MoveToWorkspace(index) => {
+            desktop
+                .active_workspaces_mut(backend)
+                .move_focused_window_to(backend, index, false);
+        }
+        Action::MoveToWorkspaceAndFollow(index) => {
+            desktop
+                .active_workspaces_mut(backend)
+                .move_focused_window_to(backend, index, true);
+        }
+        Action::FocusMonitorLeft => {
+            desktop.focus_monitor_left(backend);
+            return false;
+        }
+        Action::FocusMonitorRight => {
+            desktop.focus_monitor_right(backend);
+            return false;
+        }
+        Action::MoveToMonitorLeft => desktop.move_focused_window_to_monitor_left(backend),
+        Action::MoveToMonitorRight => desktop.move_focused_window_to_monitor_right(backend),
+        action => return execute_in_tiler(backend, desktop.active_tiler_mut(backend), action),
+    }
+    true
+}
+
+fn execute_in_tiler(backend: &impl Backend, tiler: &mut ScrollTiler, action: Action) -> bool {
+    match action {
+        Action::FocusLeft => tiler.focus_left(backend),
+        Action::FocusRight => tiler.focus_right(backend),
+        Action::FocusUp => tiler.focus_up(backend),
+        Action::FocusDown => tiler.focus_down(backend),
+        Action::SwapLeft => tiler.swap_current_left(backend),
+        Action::SwapRight => tiler.swap_current_right(backend),
+        Action::SwapUp => tiler.swap_current_up(backend),
+        Action::SwapDown => tiler.swap_current_down(backend),
+        Action::ConsumeOrExpelLeft => tiler.consume_or_expel_left(backend),
+        Action::ConsumeOrExpelRight => tiler.consume_or_expel_right(backend),
+        Action::CycleWidth => tiler.cycle_width(backend),
+        Action::SetWidth(width) => tiler.set_width(backend, width),
+        Action::CycleScrollPolicy => tiler.cycle_scroll_policy(),
+        Action::ToggleFloating => tiler.toggle_floating(backend),
+        Action::ToggleMaximized => tiler.toggle_maximized(backend),
+        Action::ToggleFullscreen => tiler.toggle_fullscreen(backend),
+        Action::SwitchToWorkspace(_)
+        | Action::MoveToWorkspace(_)
+        | Action::MoveToWorkspaceAndFollow(_)
+        | Action::FocusMonitorLeft
+        | Action::FocusMonitorRight
+        | Action::MoveToMonitorLeft
+        | Action::MoveToMonitorRight => unreachable!("{action:?} is handled by the desktop"),
+        Action::ToggleOverview | Action::Quit => {
+            unreachable!("{action:?} is handled by the event loop")
+        }
+    }
+    !matches!(
+        action,
+        Action::FocusLeft | Action::FocusRight | Action::FocusUp | Action::FocusDown
+    )
+}
@@ -0,0 +1,172 @@
+use rdev::Key;
+
+use crate::{
+    backend::{Rectangle, WindowId},
+    tiler::ScrollTiler,
+    utils::offset_index,
+};
+
+/// Returns where every tiled window goes for the whole strip to fit on screen, scaled down and
+/// centered
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    reason = "screen coordinates are small enough to be represented exactly by a f32"
+)]
+pub fn layout(tiler: &ScrollTiler) -> Vec<(WindowId, Rectangle)> {
+    let screen = tiler.area();
+    // Rectangles relative to the left of the strip instead of the screen
+    let rects = tiler
+        .window_rects()
+        .into_iter()
+        .map(|(window, rect)| {
+            let x = rect.x - screen.x + tiler.scroll_offset();
+            (window, Rectangle { x, ..rect })
+        })
+        .collect::<Vec<_>>();
+
+    // The strip has the same margin on both sides
+    let left = rects.iter().map(|(_, rect)| rect.x).min().unwrap_or(0);
+    let right = rects
+        .iter()
+        .map(|(_, rect)| rect.x + rect.width)
+        .max()
+        .unwrap_or(0);
+    let strip_width = (right + left).max(1);
+    let scale = (screen.width as f32 / strip_width as f32).min(1.0);
+
+    let scaled = |length: i32| (length as f32 * scale).round() as i32;
+    let origin_x = screen.x + (screen.width - scaled(strip_width)) / 2;
+    let origin_y = screen.y + (screen.height - scaled(screen.height)) / 2;
+    rects
+        .into_iter()
+        .map(|(window, rect)| {
+            (
+                window,
+                Rectangle {
+                    x: origin_x + scaled(rect.x),
+                    y: origin_y + scaled(rect.y - screen.y),
+                    width: scaled(rect.width),
+                    height: scaled(rect.height),
+                },
+            )
+        })
+        .collect()
+}
+
+/// What a key does while the overview is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    /// Moves the selection by this many columns
+    Select(i32),
+    Confirm,
+    Cancel,
+}
+
+impl Input {
+    pub const fn from_key(key: Key) -> Option<Self> {
+        match key {
+            Key::LeftArrow => Some(Self::Select(-1)),
+            Key::RightArrow => Some(Self::Select(1)),
+            Key::Return | Key::Space => Some(Self::Confirm),
+            Key::Escape => Some(Self::Cancel),
+            _ => None,
+        }
+    }
+}
+
+/// Strip scaled down to fit on screen, a column being selected with the arrow keys
+pub struct Overview {
+    /// Column active when the overview was opened, selected again when it is cancelled
+    initial_column: usize,
+    selected: usize,
+}
+
+impl Overview {
+    pub const fn new(tiler: &ScrollTiler) -> Self {
+        Self {
+            initial_column: tiler.active_column(),
+            selected: tiler.active_column(),
+        }
+    }
+
+    pub const fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Applies `input`, returning the column to focus once the overview closes
+    pub fn handle(&mut self, input: Input, column_count: usize) -> Option<usize> {
+        match input {
+            Input::Select(direction) => {
+                self.selected = offset_index(self.selected, direction, column_count);
+                None
+            }
+            Input::Confirm => Some(self.selected),
+            Input::Cancel => Some(self.initial_column),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::fake::{FakeBackend, sync, tiler},
+        tiler::ScrollPolicy,
+    };
+
+    fn tiler_with_columns(backend: &FakeBackend, count: usize) -> ScrollTiler {
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        for index in 0..count {
+            backend.open(&format!("window {index}"));
+            sync(&mut tiler, backend);
+        }
+        tiler
+    }
+
+    #[test]
+    fn every_column_fits_on_screen() {
+        let backend = FakeBackend::new();
+        let tiler = tiler_with_columns(&backend, 4);
+
+        // 4 columns of 500 pixels with their gaps make a 2080 pixels wide strip
+        let rects = layout(&tiler)
+            .into_iter()
+            .map(|(_, rect)| rect)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rects,
+            [5, 255, 505, 755].map(|x| Rectangle {
+                x,
+                y: 135,
+                width: 240,
+                height: 231,
+            })
+        );
+    }
+
+    #[test]
+    fn cancelling_goes_back_to_the_initial_column() {
+        let backend = FakeBackend::new();
+        let tiler = tiler_with_columns(&backend, 3);
+        let mut overview = Overview::new(&tiler);
+
+        assert_eq!(overview.handle(Input::Select(-1), 3), None);
+        assert_eq!(overview.handle(Input::Select(-1), 3), None);
+        assert_eq!(overview.handle(Input::Select(-1), 3), None);
+        assert_eq!(overview.selected(), 0);
+        assert_eq!(overview.handle(Input::Confirm, 3), Some(0));
+        assert_eq!(overview.handle(Input::Cancel, 3), Some(2));
+    }
+
+    #[test]
+    fn empty_strips_have_nothing_to_select() {
+        let backend = FakeBackend::new();
+        let tiler = tiler_with_columns(&backend, 0);
+        let mut overview = Overview::new(&tiler);
+
+        assert_eq!(overview.handle(Input::Select(1), 0), None);
+        assert_eq!(overview.handle(Input::Select(-1), 0), None);
+        assert_eq!(overview.handle(Input::Confirm, 0), Some(0));
+    }
+}
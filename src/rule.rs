@@ -0,0 +1,213 @@
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+use crate::tiler::{InsertPosition, Layer, Placement};
+
+/// Regular expression matched against window titles
+#[derive(Debug, Clone)]
+pub struct TitlePattern(Regex);
+
+impl PartialEq for TitlePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl TitlePattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TitlePattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// User rule applying actions to the windows matching every one of its set criteria
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Rule {
+    /// Exact window class
+    pub class: Option<String>,
+    /// Executable name, compared case insensitively
+    pub process: Option<String>,
+    pub title: Option<TitlePattern>,
+
+    /// Leaves the window alone instead of tiling it
+    pub ignore: Option<bool>,
+    pub float: Option<bool>,
+    /// Initial proportion of the screen width
+    pub width: Option<f32>,
+    /// Workspace number the window opens on, starting from 1
+    pub workspace: Option<usize>,
+    pub insert_position: Option<InsertPosition>,
+}
+
+/// Properties of a window the rules match on
+pub struct WindowProperties {
+    pub class: String,
+    pub process: String,
+    pub title: Option<String>,
+}
+
+/// Outcome of the rules for one window
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WindowRules {
+    pub ignore: bool,
+    /// Workspace index
+    pub workspace: Option<usize>,
+    pub placement: Placement,
+}
+
+impl Rule {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.class.is_some() || self.process.is_some() || self.title.is_some(),
+            "rules need at least one of `class`, `process` or `title` to match on"
+        );
+        if let Some(width) = self.width {
+            anyhow::ensure!(
+                width > 0.0 && width <= 1.0,
+                "rule widths are proportions of the screen and must be in (0, 1], got {width}"
+            );
+        }
+        anyhow::ensure!(
+            self.workspace != Some(0),
+            "rule workspaces are numbered from 1"
+        );
+        Ok(())
+    }
+
+    fn matches(&self, window: &WindowProperties) -> bool {
+        self.class
+            .as_ref()
+            .is_none_or(|class| *class == window.class)
+            && self
+                .process
+                .as_ref()
+                .is_none_or(|process| process.eq_ignore_ascii_case(&window.process))
+            && self.title.as_ref().is_none_or(|TitlePattern(title)| {
+                window
+                    .title
+                    .as_ref()
+                    .is_some_and(|window_title| title.is_match(window_title))
+            })
+    }
+}
+
+/// Applies every rule matching the window in order, later rules overriding earlier ones
+pub fn evaluate(rules: &[Rule], window: &WindowProperties) -> WindowRules {
+    let mut result = WindowRules::default();
+    for rule in rules.iter().filter(|rule| rule.matches(window)) {
+        if let Some(ignore) = rule.ignore {
+            result.ignore = ignore;
+        }
+        if let Some(float) = rule.float {
+            result.placement.layer = if float {
+                Layer::Floating
+            } else {
+                Layer::Tiling
+            };
+        }
+        if let Some(width) = rule.width {
+            result.placement.width = Some(width);
+        }
+        if let Some(workspace) = rule.workspace {
+            result.workspace = Some(workspace - 1);
+        }
+        if let Some(insert_position) = rule.insert_position {
+            result.placement.insert_position = Some(insert_position);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(text: &str) -> Vec<Rule> {
+        #[derive(Deserialize)]
+        struct Rules {
+            rules: Vec<Rule>,
+        }
+        toml::from_str::<Rules>(text).unwrap().rules
+    }
+
+    fn window(class: &str, process: &str, title: &str) -> WindowProperties {
+        WindowProperties {
+            class: class.to_string(),
+            process: process.to_string(),
+            title: Some(title.to_string()),
+        }
+    }
+
+    #[test]
+    fn every_criterion_has_to_match() {
+        let rules = rules(
+            r#"
+            [[rules]]
+            process = "firefox.exe"
+            title = "^Picture-in-Picture$"
+            float = true
+            "#,
+        );
+
+        let pip = evaluate(
+            &rules,
+            &window("MozillaWindowClass", "Firefox.exe", "Picture-in-Picture"),
+        );
+        assert_eq!(pip.placement.layer, Layer::Floating);
+        let browser = evaluate(
+            &rules,
+            &window("MozillaWindowClass", "firefox.exe", "Mozilla Firefox"),
+        );
+        assert_eq!(browser, WindowRules::default());
+    }
+
+    #[test]
+    fn later_rules_override_earlier_ones() {
+        let rules = rules(
+            r#"
+            [[rules]]
+            class = "Chat"
+            float = true
+            width = 0.25
+            workspace = 3
+
+            [[rules]]
+            title = "Call"
+            float = false
+            insert-position = "end"
+            "#,
+        );
+
+        let call = evaluate(&rules, &window("Chat", "chat.exe", "Call with Alice"));
+        assert_eq!(
+            call,
+            WindowRules {
+                ignore: false,
+                workspace: Some(2),
+                placement: Placement {
+                    layer: Layer::Tiling,
+                    width: Some(0.25),
+                    insert_position: Some(InsertPosition::End),
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(rules("[[rules]]\nfloat = true")[0].validate().is_err());
+        assert!(
+            rules("[[rules]]\nclass = \"A\"\nworkspace = 0")[0]
+                .validate()
+                .is_err()
+        );
+        assert!(toml::from_str::<Rule>("title = \"(\"").is_err());
+    }
+}
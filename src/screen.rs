@@ -1,10 +1,101 @@
-use windows::Win32::UI::WindowsAndMessaging::{GetSystemMetrics, SM_CXSCREEN, SM_CYSCREEN};
+use windows::{
+    Win32::{
+        Foundation::{LPARAM, RECT},
+        Graphics::Gdi::{
+            EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITOR_DEFAULTTONEAREST,
+            MONITORINFO, MonitorFromWindow,
+        },
+        UI::HiDpi::{
+            DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, GetDpiForMonitor, MDT_EFFECTIVE_DPI,
+            SetProcessDpiAwarenessContext,
+        },
+    },
+    core::BOOL,
+};
 
-use crate::wincall_into_result;
+use crate::{
+    backend::{Monitor, MonitorId},
+    wincall_into_result, wincall_result,
+    window::Window,
+};
 
-pub fn screen_size() -> anyhow::Result<(i32, i32)> {
-    Ok((
-        wincall_into_result!(GetSystemMetrics(SM_CXSCREEN))?,
-        wincall_into_result!(GetSystemMetrics(SM_CYSCREEN))?,
+impl From<HMONITOR> for MonitorId {
+    fn from(monitor: HMONITOR) -> Self {
+        Self(monitor.0 as isize)
+    }
+}
+
+fn monitor_info(handle: HMONITOR) -> anyhow::Result<Monitor> {
+    #[allow(
+        clippy::cast_possible_truncation,
+        reason = "size of small struct will never be large enough to be truncated"
+    )]
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    wincall_result!(GetMonitorInfoW(handle, &raw mut info).ok())?;
+    // Both axes have the same DPI
+    let (mut dpi, mut vertical_dpi) = (0, 0);
+    wincall_result!(GetDpiForMonitor(
+        handle,
+        MDT_EFFECTIVE_DPI,
+        &raw mut dpi,
+        &raw mut vertical_dpi
+    ))?;
+    Ok(Monitor {
+        id: handle.into(),
+        rect: info.rcMonitor.into(),
+        work_area: info.rcWork.into(),
+        dpi,
+    })
+}
+
+/// Makes Windows report the physical pixels of every monitor instead of scaling the coordinates
+/// of winri to the DPI of the primary one
+pub fn declare_dpi_awareness() -> anyhow::Result<()> {
+    wincall_result!(SetProcessDpiAwarenessContext(
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2
     ))
 }
+
+/// Returns the monitor the largest part of `window` is on
+pub fn monitor_of(window: Window) -> anyhow::Result<MonitorId> {
+    let handle =
+        wincall_into_result!(MonitorFromWindow(window.handle(), MONITOR_DEFAULTTONEAREST))?;
+    Ok(handle.into())
+}
+
+/// Returns every monitor from left to right
+pub fn monitors() -> anyhow::Result<Vec<Monitor>> {
+    unsafe extern "system" fn enum_callback(
+        monitor: HMONITOR,
+        _hdc: HDC,
+        _rect: *mut RECT,
+        out_list: LPARAM,
+    ) -> BOOL {
+        let list = unsafe { &mut *(out_list.0 as *mut Vec<HMONITOR>) };
+        list.push(monitor);
+        true.into() // Continue enumeration
+    }
+
+    let mut result = Vec::new();
+
+    wincall_result!(
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(enum_callback),
+            LPARAM(&raw mut result as isize)
+        )
+        .ok()
+    )?;
+
+    let mut monitors = result
+        .into_iter()
+        .map(monitor_info)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    monitors.sort_by_key(|monitor| (monitor.rect.x, monitor.rect.y));
+
+    Ok(monitors)
+}
@@ -0,0 +1,200 @@
+use std::{fs, io, path::PathBuf};
+
+use anyhow::Context;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::{Backend, WindowId},
+    tiler::ScrollTiler,
+};
+
+const SESSION_FILE: &str = "session.json";
+
+/// What identifies a window across restarts, unlike its handle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowIdentity {
+    pub process: String,
+    pub class: String,
+    pub title: Option<String>,
+}
+
+impl WindowIdentity {
+    pub fn of(backend: &impl Backend, window: WindowId) -> anyhow::Result<Self> {
+        Ok(Self {
+            process: backend.process_name(window)?,
+            class: backend.class(window)?,
+            title: backend.title(window)?,
+        })
+    }
+
+    fn is_same_application(&self, other: &Self) -> bool {
+        self.process == other.process && self.class == other.class
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedColumn {
+    pub windows: Vec<WindowIdentity>,
+    /// Proportion of the screen width
+    pub width: f32,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedStrip {
+    pub columns: Vec<SavedColumn>,
+    /// Floating windows, which stay where they are on screen, missing from older sessions
+    #[serde(default)]
+    pub floating: Vec<WindowIdentity>,
+    pub active_column: usize,
+    pub scroll_offset: i32,
+}
+
+impl SavedStrip {
+    /// Saves the windows of `tiler`, leaving out the ones that cannot be identified
+    pub fn new(backend: &impl Backend, tiler: &ScrollTiler) -> Self {
+        let identify = |windows: &[WindowId]| {
+            windows
+                .iter()
+                .filter_map(|window| {
+                    WindowIdentity::of(backend, *window)
+                        .inspect_err(|err| {
+                            warn!("Failed to identify window {window:?}: {err}");
+                        })
+                        .ok()
+                })
+                .collect::<Vec<_>>()
+        };
+        Self {
+            columns: tiler
+                .columns()
+                .iter()
+                .map(|column| SavedColumn {
+                    windows: identify(column.windows()),
+                    width: column.width(),
+                })
+                .filter(|column| !column.windows.is_empty())
+                .collect(),
+            floating: identify(tiler.floating_windows()),
+            active_column: tiler.active_column(),
+            scroll_offset: tiler.scroll_offset(),
+        }
+    }
+}
+
+/// Strips of every workspace of a monitor
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedMonitor {
+    pub active_workspace: usize,
+    pub workspaces: Vec<SavedStrip>,
+}
+
+/// Layout of the strips saved when they change, restored on the next startup
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Monitors from left to right
+    pub monitors: Vec<SavedMonitor>,
+}
+
+impl Session {
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|dir| dir.join("winri").join(SESSION_FILE))
+    }
+
+    /// Reads the last saved session, which is empty when there is none
+    pub fn load() -> anyhow::Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                info!("Restoring session from {}", path.display());
+                serde_json::from_str(&text)
+                    .with_context(|| format!("Invalid session {}", path.display()))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        // Renaming over the previous session keeps it whole if winri dies while writing
+        let temporary_path = path.with_extension("json.tmp");
+        fs::write(&temporary_path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write {}", temporary_path.display()))?;
+        fs::rename(&temporary_path, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Finds the opened window of every saved identity, each window being matched at most once.
+///
+/// Exact matches are found first, then windows of the same process and class whose title
+/// changed, such as an editor showing another file.
+pub fn match_windows(
+    saved: &[&WindowIdentity],
+    opened: &[(WindowId, WindowIdentity)],
+) -> Vec<Option<WindowId>> {
+    let mut matched = vec![None; saved.len()];
+    let mut available = vec![true; opened.len()];
+
+    let passes: [fn(&WindowIdentity, &WindowIdentity) -> bool; 2] =
+        [|a, b| a == b, WindowIdentity::is_same_application];
+    for is_match in passes {
+        for (identity, window) in saved.iter().zip(&mut matched) {
+            if window.is_some() {
+                continue;
+            }
+            if let Some(index) = opened
+                .iter()
+                .zip(&available)
+                .position(|((_, other), available)| *available && is_match(identity, other))
+            {
+                available[index] = false;
+                *window = Some(opened[index].0);
+            }
+        }
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(process: &str, title: &str) -> WindowIdentity {
+        WindowIdentity {
+            process: process.to_string(),
+            class: "Window".to_string(),
+            title: Some(title.to_string()),
+        }
+    }
+
+    #[test]
+    fn exact_matches_win_over_renamed_windows() {
+        let opened = [
+            (WindowId(1), identity("code", "main.rs")),
+            (WindowId(2), identity("code", "lib.rs")),
+            (WindowId(3), identity("terminal", "shell")),
+        ];
+
+        let saved = [
+            identity("code", "config.rs"),
+            identity("code", "lib.rs"),
+            identity("browser", "docs"),
+        ];
+        assert_eq!(
+            match_windows(&saved.iter().collect::<Vec<_>>(), &opened),
+            [Some(WindowId(1)), Some(WindowId(2)), None]
+        );
+    }
+}
@@ -1,189 +1,1178 @@
-use std::{collections::HashSet, ops::Sub};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Sub,
+};
 
-use log::{error, warn};
+use log::{error, info, warn};
+use serde::Deserialize;
 
-use crate::window::Window;
+use crate::{
+    backend::{Backend, DEFAULT_DPI, Monitor, Rectangle, WindowId},
+    event::StateEvent,
+    utils::offset_index,
+};
 
-#[derive(PartialEq, Eq)]
-pub struct WindowItem {
-    inner: Window,
-    width: i32,
+/// How a column temporarily takes over the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpandMode {
+    /// The column spans the usable screen width without gaps
+    Maximized,
+    /// The focused window of the column covers the whole monitor
+    Fullscreen,
 }
 
-impl WindowItem {
-    pub const fn new(inner: Window, width: i32) -> Self {
-        Self { inner, width }
+/// State of a column before it was expanded, restored when the expansion is toggled off
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Expansion {
+    mode: ExpandMode,
+    width: f32,
+    scroll_offset: i32,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Column {
+    windows: Vec<WindowId>,
+    /// Proportion of the screen width
+    width: f32,
+    active: usize,
+    expansion: Option<Expansion>,
+}
+
+impl Column {
+    pub fn new(window: WindowId, width: f32) -> Self {
+        Self {
+            windows: vec![window],
+            width,
+            active: 0,
+            expansion: None,
+        }
+    }
+
+    pub fn windows(&self) -> &[WindowId] {
+        &self.windows
+    }
+
+    /// Proportion of the screen width
+    pub const fn width(&self) -> f32 {
+        self.width
+    }
+
+    fn is_expanded(&self, mode: ExpandMode) -> bool {
+        self.expansion
+            .is_some_and(|expansion| expansion.mode == mode)
+    }
+
+    fn focus_index(&self, focused_window: WindowId) -> Option<usize> {
+        self.windows
+            .iter()
+            .position(|window| *window == focused_window)
+    }
+
+    fn contains(&self, window: WindowId) -> bool {
+        self.windows.contains(&window)
+    }
+
+    fn active_window(&self) -> WindowId {
+        self.windows[self.active.min(self.windows.len() - 1)]
+    }
+}
+
+/// How the strip scrolls when the focused column changes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScrollPolicy {
+    /// Scroll the minimal distance needed to bring the focused column on screen
+    #[default]
+    Minimal,
+    /// Always keep the focused column in the middle of the screen
+    Center,
+    /// Center the focused column only when it is not entirely on screen
+    CenterIfNotFit,
+}
+
+impl ScrollPolicy {
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Minimal => Self::Center,
+            Self::Center => Self::CenterIfNotFit,
+            Self::CenterIfNotFit => Self::Minimal,
+        }
     }
 }
 
-#[derive(Default)]
+/// Space reserved on each edge of the screen, for external bars
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Edges {
+    pub top: i32,
+    pub bottom: i32,
+    pub left: i32,
+    pub right: i32,
+}
+
+/// Spacing of the strip in logical pixels, scaled by the DPI of the monitor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Gaps {
+    /// Space between two columns and between the windows of a column
+    pub inner: i32,
+    /// Space between the windows and the edges of the usable area
+    pub outer: i32,
+    pub reserved: Edges,
+}
+
+/// Where new windows are inserted in the strip
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InsertPosition {
+    #[default]
+    AfterFocused,
+    BeforeFocused,
+    End,
+    Start,
+}
+
+/// Whether a window is part of the strip or floats above it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    #[default]
+    Tiling,
+    Floating,
+}
+
+/// How a window enters the tiler, the unset fields falling back to the tiler settings
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub layer: Layer,
+    /// Proportion of the screen width
+    pub width: Option<f32>,
+    pub insert_position: Option<InsertPosition>,
+}
+
+#[derive(Default, Clone)]
 pub struct ScrollTiler {
-    windows: Vec<WindowItem>,
-    padding: i32,
+    columns: Vec<Column>,
+    active_column: usize,
+    /// Windows outside of the strip, keeping their own geometry
+    floating: Vec<WindowId>,
+    /// Geometry to give back to tiled windows when they start floating
+    floating_rects: HashMap<WindowId, Rectangle>,
+    /// Set when the strip changed in a way that requires a layout pass even if the window
+    /// snapshot did not change
+    layout_pending: bool,
+    /// Set while the overview shows the strip and places its windows
+    in_overview: bool,
+    gaps: Gaps,
+    /// Proportions of the screen width a column can be cycled through
+    width_presets: Vec<f32>,
+    default_width: f32,
+    scroll_policy: ScrollPolicy,
+    insert_position: InsertPosition,
     scroll_offset: i32,
-    screen_width: i32,
-    screen_height: i32,
+    /// Whole monitor, which fullscreen columns cover
+    screen: Rectangle,
+    /// Part of the monitor left by the taskbar, which the strip is laid out in
+    work_area: Rectangle,
+    /// Dots per inch of the monitor, scaling the gaps
+    dpi: u32,
+    /// Last focused window of the tiler, cleared when the focus leaves it
+    focused: Option<WindowId>,
+    /// Window focused by hovering it, which the strip does not scroll to so that it stays under
+    /// the pointer
+    pointer_focus: Option<WindowId>,
+    /// Changes not yet collected by `take_events`
+    events: Vec<StateEvent>,
 }
 
 impl ScrollTiler {
-    pub fn new(padding: i32, screen_width: i32, screen_height: i32) -> Self {
+    pub fn new(
+        gaps: Gaps,
+        width_presets: Vec<f32>,
+        default_width: f32,
+        scroll_policy: ScrollPolicy,
+        insert_position: InsertPosition,
+        monitor: &Monitor,
+    ) -> Self {
         Self {
-            padding,
-            screen_width,
-            screen_height,
+            gaps,
+            width_presets,
+            default_width,
+            scroll_policy,
+            insert_position,
+            screen: monitor.rect,
+            work_area: monitor.work_area,
+            dpi: monitor.dpi,
             ..Default::default()
         }
     }
 
-    fn focus_index(&self) -> Option<usize> {
-        self.windows
+    /// Returns the focused window position as `(column index, index in column)`
+    fn focus_index(&self, backend: &impl Backend) -> Option<(usize, usize)> {
+        let focused_window = backend.focused_window()?;
+        self.columns
             .iter()
-            .position(|item| item.inner.is_focused().unwrap_or(false))
+            .enumerate()
+            .find_map(|(column_index, column)| {
+                column
+                    .focus_index(focused_window)
+                    .map(|window_index| (column_index, window_index))
+            })
     }
 
-    pub fn swap_current_left(&mut self) {
-        self.swap_current(-1);
+    fn warn_focus_not_found(backend: &impl Backend) {
+        warn!(
+            "Could not find focused window in tiler. Focused window is {:?}",
+            backend.focused_window()
+        );
     }
 
-    pub fn swap_current_right(&mut self) {
-        self.swap_current(1);
+    fn focus_window(backend: &impl Backend, window: WindowId) {
+        if let Err(err) = backend.focus(window) {
+            error!(
+                "Failed to focus window ({}): {}",
+                err,
+                backend.describe(window),
+            );
+        }
     }
 
-    fn swap_current(&mut self, direction: i32) {
-        if let Some(focus_index) = self.focus_index() {
-            #[allow(
-                clippy::cast_possible_truncation,
-                clippy::cast_sign_loss,
-                clippy::cast_possible_wrap,
-                reason = "to add a potential negative number to a usize"
-            )]
-            let other_swap_index =
-                (focus_index as i32 + direction).clamp(0, self.windows.len() as i32 - 1) as usize;
-            self.windows.swap(focus_index, other_swap_index);
+    pub fn swap_current_left(&mut self, backend: &impl Backend) {
+        self.swap_current(backend, -1);
+    }
+
+    pub fn swap_current_right(&mut self, backend: &impl Backend) {
+        self.swap_current(backend, 1);
+    }
+
+    fn swap_current(&mut self, backend: &impl Backend, direction: i32) {
+        if let Some((column_index, _)) = self.focus_index(backend) {
+            let other_swap_index = offset_index(column_index, direction, self.columns.len());
+            if other_swap_index == column_index {
+                return;
+            }
+            self.columns.swap(column_index, other_swap_index);
+            self.events.push(StateEvent::ColumnMoved {
+                from: column_index,
+                to: other_swap_index,
+            });
+            self.layout_pending = true;
         } else {
-            warn!(
-                "Could not find focused window in tiler. Focused window is {:?}",
-                Window::focused()
-            );
+            Self::warn_focus_not_found(backend);
         }
     }
 
-    pub fn focus_left(&self) {
-        self.focus(-1);
+    /// Moves the column holding `window` to `index`, shifting the columns in between
+    pub fn move_column(&mut self, window: WindowId, index: usize) {
+        let Some(from) = self
+            .columns
+            .iter()
+            .position(|column| column.contains(window))
+        else {
+            return;
+        };
+        let to = index.min(self.columns.len() - 1);
+        if from == to {
+            return;
+        }
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+        self.events.push(StateEvent::ColumnMoved { from, to });
+        self.layout_pending = true;
     }
 
-    pub fn focus_right(&self) {
-        self.focus(1);
+    pub fn swap_current_up(&mut self, backend: &impl Backend) {
+        self.swap_current_in_column(backend, -1);
     }
 
-    fn focus(&self, direction: i32) {
-        if let Some(focus_index) = self.focus_index() {
-            #[allow(
-                clippy::cast_possible_truncation,
-                clippy::cast_sign_loss,
-                clippy::cast_possible_wrap,
-                reason = "to add a potential negative number to a usize"
-            )]
-            let new_focus_index =
-                (focus_index as i32 + direction).clamp(0, self.windows.len() as i32 - 1) as usize;
-            let window = self.windows[new_focus_index].inner;
-            if let Err(err) = window.focus() {
-                error!(
-                    "Failed to focus window ({}): {}",
-                    err,
-                    window.get_formatted_extensive_info(),
-                );
+    pub fn swap_current_down(&mut self, backend: &impl Backend) {
+        self.swap_current_in_column(backend, 1);
+    }
+
+    fn swap_current_in_column(&mut self, backend: &impl Backend, direction: i32) {
+        if let Some((column_index, window_index)) = self.focus_index(backend) {
+            let column = &mut self.columns[column_index];
+            let other_swap_index = offset_index(window_index, direction, column.windows.len());
+            column.windows.swap(window_index, other_swap_index);
+            column.active = other_swap_index;
+            self.push_columns_changed();
+            self.layout_pending = true;
+        } else {
+            Self::warn_focus_not_found(backend);
+        }
+    }
+
+    pub fn consume_or_expel_left(&mut self, backend: &impl Backend) {
+        self.consume_or_expel(backend, -1);
+    }
+
+    pub fn consume_or_expel_right(&mut self, backend: &impl Backend) {
+        self.consume_or_expel(backend, 1);
+    }
+
+    /// Moves the focused window out of its column into a new column next to it, or into the
+    /// neighbouring column if it is alone in its own
+    fn consume_or_expel(&mut self, backend: &impl Backend, direction: i32) {
+        let Some((column_index, window_index)) = self.focus_index(backend) else {
+            Self::warn_focus_not_found(backend);
+            return;
+        };
+
+        let column = &mut self.columns[column_index];
+        self.layout_pending = true;
+
+        if column.windows.len() > 1 {
+            let window = column.windows.remove(window_index);
+            column.active = column.active.min(column.windows.len() - 1);
+            let new_column = Column::new(window, column.width);
+            let insert_index = if direction < 0 {
+                column_index
+            } else {
+                column_index + 1
+            };
+            self.columns.insert(insert_index, new_column);
+            self.push_columns_changed();
+            return;
+        }
+
+        let other_column_index = offset_index(column_index, direction, self.columns.len());
+        if other_column_index == column_index {
+            return;
+        }
+        let column = self.columns.remove(column_index);
+        let other_column_index = if other_column_index > column_index {
+            other_column_index - 1
+        } else {
+            other_column_index
+        };
+        let other_column = &mut self.columns[other_column_index];
+        other_column.windows.extend(column.windows);
+        other_column.active = other_column.windows.len() - 1;
+        self.push_columns_changed();
+    }
+
+    fn push_columns_changed(&mut self) {
+        self.events.push(StateEvent::ColumnsChanged {
+            columns: self
+                .columns
+                .iter()
+                .map(|column| column.windows.clone())
+                .collect(),
+        });
+    }
+
+    pub fn focus_left(&self, backend: &impl Backend) {
+        self.focus(backend, -1);
+    }
+
+    pub fn focus_right(&self, backend: &impl Backend) {
+        self.focus(backend, 1);
+    }
+
+    fn is_floating_focused(&self, backend: &impl Backend) -> bool {
+        backend
+            .focused_window()
+            .is_some_and(|window| self.floating.contains(&window))
+    }
+
+    fn focus(&self, backend: &impl Backend, direction: i32) {
+        if let Some((column_index, _)) = self.focus_index(backend) {
+            let new_focus_index = offset_index(column_index, direction, self.columns.len());
+            Self::focus_window(backend, self.columns[new_focus_index].active_window());
+        } else if self.is_floating_focused(backend) {
+            // Going back to the strip from a floating window
+            self.focus_active(backend);
+        } else {
+            Self::warn_focus_not_found(backend);
+        }
+    }
+
+    pub fn focus_up(&mut self, backend: &impl Backend) {
+        self.focus_in_column(backend, -1);
+    }
+
+    pub fn focus_down(&mut self, backend: &impl Backend) {
+        self.focus_in_column(backend, 1);
+    }
+
+    fn focus_in_column(&mut self, backend: &impl Backend, direction: i32) {
+        if let Some((column_index, window_index)) = self.focus_index(backend) {
+            let column = &mut self.columns[column_index];
+            column.active = offset_index(window_index, direction, column.windows.len());
+            Self::focus_window(backend, column.active_window());
+        } else {
+            Self::warn_focus_not_found(backend);
+        }
+    }
+
+    pub fn cycle_scroll_policy(&mut self) {
+        self.scroll_policy = self.scroll_policy.next();
+        info!("Scroll policy is now {:?}", self.scroll_policy);
+    }
+
+    /// Switches the focused column to the next width preset, wrapping around after the largest
+    pub fn cycle_width(&mut self, backend: &impl Backend) {
+        let Some((column_index, _)) = self.focus_index(backend) else {
+            Self::warn_focus_not_found(backend);
+            return;
+        };
+        let column = &self.columns[column_index];
+        let next_preset = self
+            .width_presets
+            .iter()
+            .copied()
+            .find(|preset| *preset > column.width + f32::EPSILON)
+            .or_else(|| self.width_presets.first().copied());
+        if let Some(width) = next_preset {
+            self.resize_column(column_index, width);
+            self.columns[column_index].expansion = None;
+            self.layout_pending = true;
+        }
+    }
+
+    /// Sets the width of the focused column to a proportion of the screen width
+    pub fn set_width(&mut self, backend: &impl Backend, width: f32) {
+        let Some((column_index, _)) = self.focus_index(backend) else {
+            Self::warn_focus_not_found(backend);
+            return;
+        };
+        self.resize_column(column_index, width);
+        self.columns[column_index].expansion = None;
+        self.layout_pending = true;
+    }
+
+    /// Makes the focused column span the usable screen width without gaps, or restores it
+    pub fn toggle_maximized(&mut self, backend: &impl Backend) {
+        self.toggle_expansion(backend, ExpandMode::Maximized);
+    }
+
+    /// Makes the focused window cover the whole monitor, or restores its column
+    pub fn toggle_fullscreen(&mut self, backend: &impl Backend) {
+        self.toggle_expansion(backend, ExpandMode::Fullscreen);
+    }
+
+    fn toggle_expansion(&mut self, backend: &impl Backend, mode: ExpandMode) {
+        let Some((column_index, _)) = self.focus_index(backend) else {
+            Self::warn_focus_not_found(backend);
+            return;
+        };
+        let column = &mut self.columns[column_index];
+
+        match column.expansion.take() {
+            Some(expansion) if expansion.mode == mode => {
+                self.resize_column(column_index, expansion.width);
+                self.scroll_to(expansion.scroll_offset);
             }
+            previous => {
+                // Switching between modes keeps the state from before the first expansion
+                let (width, scroll_offset) = previous
+                    .map_or((column.width, self.scroll_offset), |expansion| {
+                        (expansion.width, expansion.scroll_offset)
+                    });
+                column.expansion = Some(Expansion {
+                    mode,
+                    width,
+                    scroll_offset,
+                });
+                self.resize_column(column_index, 1.0);
+                self.scroll_to(self.windows_positions()[column_index]);
+            }
+        }
+        self.layout_pending = true;
+    }
+
+    fn resize_column(&mut self, column_index: usize, width: f32) {
+        let column = &mut self.columns[column_index];
+        if (column.width - width).abs() > f32::EPSILON {
+            self.events.push(StateEvent::WidthChanged {
+                column: column_index,
+                width,
+            });
+        }
+        column.width = width;
+    }
+
+    fn scroll_to(&mut self, scroll_offset: i32) {
+        if scroll_offset != self.scroll_offset {
+            self.events.push(StateEvent::ScrollOffsetChanged {
+                offset: scroll_offset,
+            });
+            self.scroll_offset = scroll_offset;
+        }
+    }
+
+    /// Lays the strip out again on the new geometry of its monitor, after a resolution or
+    /// taskbar change. Column widths being proportions of the screen, they scale along with it,
+    /// and so does the scroll offset.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        reason = "screen sizes are small enough to be represented exactly by a f32"
+    )]
+    pub fn set_monitor(&mut self, monitor: &Monitor) {
+        if self.screen == monitor.rect
+            && self.work_area == monitor.work_area
+            && self.dpi == monitor.dpi
+        {
+            return;
+        }
+
+        let previous_width = self.area().width;
+        self.screen = monitor.rect;
+        self.work_area = monitor.work_area;
+        self.dpi = monitor.dpi;
+        if previous_width > 0 {
+            let scale = self.area().width as f32 / previous_width as f32;
+            self.scroll_to((self.scroll_offset as f32 * scale).round() as i32);
+        }
+        self.layout_pending = true;
+    }
+
+    /// Returns the changes of the tiler since the last call
+    pub fn take_events(&mut self) -> Vec<StateEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        reason = "screen sizes are small enough to be represented exactly by a f32"
+    )]
+    fn column_width(&self, column: &Column) -> i32 {
+        (self.area().width as f32 * column.width).round() as i32
+    }
+
+    /// Converts a length in logical pixels to physical pixels of the monitor
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        reason = "screen sizes are small enough to be represented exactly by a f32"
+    )]
+    fn scaled(&self, length: i32) -> i32 {
+        (length as f32 * self.dpi as f32 / DEFAULT_DPI as f32).round() as i32
+    }
+
+    /// Space between the windows of the column and the edges of the usable area, which expanded
+    /// columns drop
+    fn outer_gap(&self, column: &Column) -> i32 {
+        if column.expansion.is_some() {
+            0
         } else {
-            warn!(
-                "Could not find focused window in tiler. Focused window is {:?}",
-                Window::focused()
-            );
+            self.scaled(self.gaps.outer)
         }
     }
 
-    pub fn handle_window_snapshot(&mut self, windows_snapshot: &HashSet<Window>) {
-        if windows_snapshot.is_empty() {
-            self.windows.clear();
+    /// Space between the windows of the column and next to it, which expanded columns drop
+    fn inner_gap(&self, column: &Column) -> i32 {
+        if column.expansion.is_some() {
+            0
+        } else {
+            self.scaled(self.gaps.inner)
+        }
+    }
+
+    /// Returns the part of the work area windows are laid out in, without the reserved edges
+    pub fn area(&self) -> Rectangle {
+        let reserved = Edges {
+            top: self.scaled(self.gaps.reserved.top),
+            bottom: self.scaled(self.gaps.reserved.bottom),
+            left: self.scaled(self.gaps.reserved.left),
+            right: self.scaled(self.gaps.reserved.right),
+        };
+        Rectangle {
+            x: self.work_area.x + reserved.left,
+            y: self.work_area.y + reserved.top,
+            width: self.work_area.width - reserved.left - reserved.right,
+            height: self.work_area.height - reserved.top - reserved.bottom,
+        }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    pub fn floating_windows(&self) -> &[WindowId] {
+        &self.floating
+    }
+
+    pub const fn active_column(&self) -> usize {
+        self.active_column
+    }
+
+    pub const fn scroll_offset(&self) -> i32 {
+        self.scroll_offset
+    }
+
+    /// Makes the next snapshot lay the strip out even if nothing changed
+    pub const fn request_layout(&mut self) {
+        self.layout_pending = true;
+    }
+
+    /// Returns the tiled windows from left to right, then the floating ones
+    pub fn windows(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.columns
+            .iter()
+            .flat_map(|column| column.windows.iter().copied())
+            .chain(self.floating.iter().copied())
+    }
+
+    pub fn contains(&self, window: WindowId) -> bool {
+        self.columns.iter().any(|column| column.contains(window)) || self.floating.contains(&window)
+    }
+
+    /// Moves the focused window from the strip to the floating layer or the other way around
+    pub fn toggle_floating(&mut self, backend: &impl Backend) {
+        let Some(window) = backend.focused_window() else {
             return;
+        };
+
+        if self.floating.contains(&window) {
+            self.set_layer(backend, window, Layer::Tiling);
+        } else if self.contains(window) {
+            self.set_layer(backend, window, Layer::Floating);
+        } else {
+            Self::warn_focus_not_found(backend);
         }
+    }
 
-        let len_before_deletion = self.windows.len();
+    /// Moves a window from the other layer of the tiler to `layer`, giving floating windows their
+    /// geometry back
+    fn set_layer(&mut self, backend: &impl Backend, window: WindowId, layer: Layer) {
+        if self.take_window(window).is_none() {
+            return;
+        }
 
-        self.windows
-            .retain(|item| windows_snapshot.contains(&item.inner));
+        match layer {
+            Layer::Tiling => {
+                self.remember_rect(backend, window);
+                self.insert_window(window, Layer::Tiling);
+            }
+            Layer::Floating => {
+                let rect = self
+                    .floating_rects
+                    .remove(&window)
+                    .unwrap_or_else(|| self.default_floating_rect());
+                self.push_floating(window);
+                if let Err(err) = backend.move_window(window, rect) {
+                    warn!("Failed to move window {window:?}: {err}");
+                }
+            }
+        }
+    }
 
-        // Early return optimization
-        if windows_snapshot.len() == self.windows.len() && len_before_deletion == self.windows.len()
+    /// Applies the layer and width of `placement` to a window already in the tiler
+    pub fn apply_placement(
+        &mut self,
+        backend: &impl Backend,
+        window: WindowId,
+        placement: &Placement,
+    ) {
+        if !self.contains(window) {
+            return;
+        }
+        if self.floating.contains(&window) != (placement.layer == Layer::Floating) {
+            self.set_layer(backend, window, placement.layer);
+        }
+        if let Some(width) = placement.width
+            && let Some(column_index) = self
+                .columns
+                .iter()
+                .position(|column| column.contains(window))
         {
-            let windows_positions = self.windows_positions();
+            self.resize_column(column_index, width);
+            self.columns[column_index].expansion = None;
+        }
+        self.layout_pending = true;
+    }
+
+    /// Centered rectangle for floating windows whose geometry is unknown
+    fn default_floating_rect(&self) -> Rectangle {
+        Rectangle {
+            x: self.area().x + self.area().width / 4,
+            y: self.area().y + self.area().height / 4,
+            width: self.area().width / 2,
+            height: self.area().height / 2,
+        }
+    }
+
+    /// Removes the focused window from the tiler, dropping its column if it becomes empty
+    pub fn take_focused_window(&mut self, backend: &impl Backend) -> Option<(WindowId, Layer)> {
+        let Some(window) = backend
+            .focused_window()
+            .filter(|window| self.contains(*window))
+        else {
+            Self::warn_focus_not_found(backend);
+            return None;
+        };
+        self.take_window(window).map(|layer| (window, layer))
+    }
+
+    /// Removes a window from the tiler, returning the layer it was on
+    fn take_window(&mut self, window: WindowId) -> Option<Layer> {
+        if let Some(index) = self.floating.iter().position(|other| *other == window) {
+            self.floating.remove(index);
+            self.window_removed(window);
+            return Some(Layer::Floating);
+        }
+
+        let column_index = self
+            .columns
+            .iter()
+            .position(|column| column.contains(window))?;
+        let column = &mut self.columns[column_index];
+        column.windows.retain(|other| *other != window);
+        if column.windows.is_empty() {
+            self.columns.remove(column_index);
+        } else {
+            column.active = column.active.min(column.windows.len() - 1);
+        }
+        self.window_removed(window);
+        self.layout_pending = true;
+        Some(Layer::Tiling)
+    }
+
+    fn window_removed(&mut self, window: WindowId) {
+        if self.focused == Some(window) {
+            self.focused = None;
+        }
+        self.events.push(StateEvent::WindowRemoved { window });
+    }
 
-            if self.ajust_scroll(&windows_positions) {
-                self.layout_windows(&windows_positions);
+    fn push_floating(&mut self, window: WindowId) {
+        self.floating.push(window);
+        self.events.push(StateEvent::WindowAdded {
+            window,
+            floating: true,
+        });
+    }
+
+    fn insert_column(&mut self, index: usize, column: Column) {
+        self.events.push(StateEvent::WindowAdded {
+            window: column.active_window(),
+            floating: false,
+        });
+        self.columns.insert(index, column);
+    }
+
+    /// Adds a window to the floating layer, or in a new column that becomes the active one
+    pub fn insert_window(&mut self, window: WindowId, layer: Layer) {
+        self.place_window(
+            window,
+            &Placement {
+                layer,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Adds a window where `placement` says, tiled windows becoming the active column
+    pub fn place_window(&mut self, window: WindowId, placement: &Placement) {
+        if placement.layer == Layer::Floating {
+            self.push_floating(window);
+            return;
+        }
+
+        let index = self.insert_index_at(placement.insert_position.unwrap_or(self.insert_position));
+        let width = placement.width.unwrap_or(self.default_width);
+        self.insert_column(index, Column::new(window, width));
+        self.active_column = index;
+        self.layout_pending = true;
+    }
+
+    /// Rebuilds the strip from saved columns of windows and their widths along with the floating
+    /// windows, windows already in the tiler staying where they are
+    pub fn restore(
+        &mut self,
+        backend: &impl Backend,
+        columns: Vec<(Vec<WindowId>, f32)>,
+        floating: Vec<WindowId>,
+        active_column: usize,
+        scroll_offset: i32,
+    ) {
+        for (windows, width) in columns {
+            let mut windows = windows.into_iter().filter(|window| !self.contains(*window));
+            let Some(first) = windows.next() else {
+                continue;
+            };
+            let mut column = Column::new(first, width);
+            column.windows.extend(windows);
+            for window in column.windows.clone() {
+                self.remember_rect(backend, window);
+                self.events.push(StateEvent::WindowAdded {
+                    window,
+                    floating: false,
+                });
+            }
+            self.columns.push(column);
+        }
+        for window in floating {
+            if !self.contains(window) {
+                self.push_floating(window);
             }
+        }
+        self.active_column = active_column.min(self.columns.len().saturating_sub(1));
+        self.scroll_to(scroll_offset);
+        self.layout_pending = true;
+    }
+
+    /// Keeps the geometry of a window entering the strip, to give it back if it starts floating
+    fn remember_rect(&mut self, backend: &impl Backend, window: WindowId) {
+        match backend.window_rect(window) {
+            Ok(rect) => {
+                self.floating_rects.insert(window, rect);
+            }
+            Err(err) => warn!("Failed to get geometry of window {window:?}: {err}"),
+        }
+    }
+
+    /// Returns the index a new column should be inserted at according to the insert position
+    fn insert_index(&self) -> usize {
+        self.insert_index_at(self.insert_position)
+    }
+
+    fn insert_index_at(&self, insert_position: InsertPosition) -> usize {
+        let active_column = self.active_column.min(self.columns.len().saturating_sub(1));
+        match insert_position {
+            InsertPosition::AfterFocused if !self.columns.is_empty() => active_column + 1,
+            InsertPosition::AfterFocused | InsertPosition::End => self.columns.len(),
+            InsertPosition::BeforeFocused => active_column,
+            InsertPosition::Start => 0,
+        }
+    }
+
+    fn update_active_column(&mut self, backend: &impl Backend) {
+        if let Some((column_index, window_index)) = self.focus_index(backend) {
+            self.active_column = column_index;
+            self.columns[column_index].active = window_index;
+        }
+
+        let focused = backend
+            .focused_window()
+            .filter(|window| self.contains(*window));
+        if focused != self.pointer_focus {
+            self.pointer_focus = None;
+        }
+        if focused != self.focused {
+            self.focused = focused;
+            if let Some(window) = focused {
+                self.events.push(StateEvent::FocusChanged { window });
+            }
+        }
+    }
+
+    /// Forgets the focused window, so that focusing it again is reported as a change
+    pub fn clear_focus(&mut self) {
+        self.focused = None;
+    }
+
+    pub fn retain_windows(&mut self, mut f: impl FnMut(WindowId) -> bool) {
+        let mut removed = Vec::new();
+        let mut keep = |window: WindowId| {
+            let keep = f(window);
+            if !keep {
+                removed.push(window);
+            }
+            keep
+        };
+        for column in &mut self.columns {
+            column.windows.retain(|window| keep(*window));
+            column.active = column.active.min(column.windows.len().saturating_sub(1));
+        }
+        self.columns.retain(|column| !column.windows.is_empty());
+        self.floating.retain(|window| keep(*window));
+        self.floating_rects
+            .retain(|window, _| !removed.contains(window) && f(*window));
+        for window in removed {
+            self.window_removed(window);
+        }
+    }
+
+    /// Leaves the windows to the overview while it shows the strip, which is laid out again once
+    /// it closes
+    pub fn set_in_overview(&mut self, in_overview: bool) {
+        self.in_overview = in_overview;
+        self.layout_pending = true;
+    }
+
+    /// Takes the columns and floating windows of `other`, such as the strip of a disconnected
+    /// monitor, after its own
+    pub fn append(&mut self, other: Self) {
+        for column in other.columns {
+            for window in &column.windows {
+                self.events.push(StateEvent::WindowAdded {
+                    window: *window,
+                    floating: false,
+                });
+            }
+            self.columns.push(column);
+        }
+        for window in other.floating {
+            self.push_floating(window);
+        }
+        self.floating_rects.extend(other.floating_rects);
+        self.layout_pending = true;
+    }
+
+    /// Drops a window that no longer exists, the next `refresh` closing the gap it leaves
+    pub fn remove_window(&mut self, window: WindowId) {
+        if self.contains(window) {
+            self.retain_windows(|other| other != window);
+            self.layout_pending = true;
+        }
+    }
+
+    /// Follows the focus and lays the strip out again if needed, without a new snapshot
+    pub fn refresh(&mut self, backend: &impl Backend) {
+        self.update_active_column(backend);
+        let windows_positions = self.windows_positions();
+
+        // The flag is read while scrolling, and cleared even when the scroll lays the strip out
+        let scrolled = self.ajust_scroll(backend, &windows_positions);
+        if std::mem::take(&mut self.layout_pending) || scrolled {
+            self.layout_windows(backend, &windows_positions);
+        }
+    }
+
+    /// Focuses a window the pointer rests on, without scrolling the strip to it
+    pub fn focus_under_pointer(&mut self, backend: &impl Backend, window: WindowId) {
+        if self.contains(window) {
+            self.pointer_focus = Some(window);
+            Self::focus_window(backend, window);
+        }
+    }
+
+    /// Focuses the window that was last focused in the column at `index`
+    pub fn focus_column(&self, backend: &impl Backend, index: usize) {
+        if let Some(column) = self.columns.get(index) {
+            Self::focus_window(backend, column.active_window());
+        }
+    }
+
+    /// Focuses the window that was last focused in the strip
+    pub fn focus_active(&self, backend: &impl Backend) {
+        if let Some(column) = self
+            .columns
+            .get(self.active_column.min(self.columns.len().saturating_sub(1)))
+        {
+            Self::focus_window(backend, column.active_window());
+        }
+    }
+
+    fn window_count(&self) -> usize {
+        self.columns
+            .iter()
+            .map(|column| column.windows.len())
+            .sum::<usize>()
+            + self.floating.len()
+    }
+
+    /// Syncs the tiler with the windows of the snapshot, `placement` telling how the new ones
+    /// are inserted
+    pub fn handle_window_snapshot(
+        &mut self,
+        backend: &impl Backend,
+        windows_snapshot: &[WindowId],
+        placement: &impl Fn(WindowId) -> Placement,
+    ) {
+        if windows_snapshot.is_empty() {
+            self.retain_windows(|_| false);
             return;
         }
-        self.append_new_windows(windows_snapshot);
+
+        let len_before_deletion = self.window_count();
+
+        let snapshot_set = windows_snapshot.iter().copied().collect::<HashSet<_>>();
+        self.retain_windows(|window| snapshot_set.contains(&window));
+
+        self.update_active_column(backend);
+
+        // Early return optimization
+        if windows_snapshot.len() == self.window_count()
+            && len_before_deletion == self.window_count()
+        {
+            self.refresh(backend);
+            return;
+        }
+        self.layout_pending = false;
+        self.insert_new_windows(backend, windows_snapshot, placement);
+        self.update_active_column(backend);
 
         let windows_positions = self.windows_positions();
 
-        self.ajust_scroll(&windows_positions);
-        self.layout_windows(&windows_positions);
+        self.ajust_scroll(backend, &windows_positions);
+        self.layout_windows(backend, &windows_positions);
     }
 
-    fn append_new_windows(&mut self, windows_snapshot: &HashSet<Window>) {
+    /// Inserts the windows of the snapshot that are not in the strip yet, keeping their order in
+    /// the snapshot
+    fn insert_new_windows(
+        &mut self,
+        backend: &impl Backend,
+        windows_snapshot: &[WindowId],
+        placement: &impl Fn(WindowId) -> Placement,
+    ) {
+        let mut index = self.insert_index();
         for window in windows_snapshot {
-            if !self
-                .windows
-                .iter()
-                .any(|window_item| window_item.inner == *window)
-            {
-                self.windows.push(WindowItem::new(
-                    *window,
-                    (self.screen_width as f32 / 1.5).round() as i32,
-                ));
+            if self.contains(*window) {
+                continue;
+            }
+
+            let placement = placement(*window);
+            if placement.layer == Layer::Floating {
+                self.push_floating(*window);
+                continue;
+            }
+
+            self.remember_rect(backend, *window);
+            let column = Column::new(*window, placement.width.unwrap_or(self.default_width));
+            let column_index = placement
+                .insert_position
+                .map_or(index, |position| self.insert_index_at(position));
+            self.insert_column(column_index, column);
+            if column_index <= self.active_column && self.columns.len() > 1 {
+                self.active_column += 1;
+            }
+            if column_index <= index {
+                index += 1;
             }
         }
     }
 
-    fn layout_windows(&self, windows_positions: &[i32]) {
-        for (window, x) in self.windows.iter().zip(windows_positions) {
-            let y = self.padding;
-            let height = self.screen_height - self.padding * 2;
-            if let Err(err) =
-                window
-                    .inner
-                    .move_window(x - self.scroll_offset, y, window.width, height)
+    fn layout_windows(&mut self, backend: &impl Backend, windows_positions: &[i32]) {
+        if self.in_overview {
+            self.layout_pending = true;
+            return;
+        }
+        for (window, rect) in self.window_rects_at(windows_positions) {
+            if let Err(err) = backend.move_window(window, rect) {
+                warn!("Failed to move window {window:?}: {err}");
+            }
+        }
+    }
+
+    /// Returns where every tiled window goes at the current scroll offset
+    pub fn window_rects(&self) -> Vec<(WindowId, Rectangle)> {
+        self.window_rects_at(&self.windows_positions())
+    }
+
+    /// Moves the tiled windows scrolled off screen back inside the work area, keeping their size,
+    /// so that they can still be reached once winri exits
+    pub fn bring_into_view(&self, backend: &impl Backend) {
+        let area = self.area();
+        for (window, rect) in self.window_rects() {
+            let x = rect.x.min(area.x + area.width - rect.width).max(area.x);
+            if x != rect.x
+                && let Err(err) = backend.move_window(window, Rectangle { x, ..rect })
             {
-                warn!("Failed to move window {:?}: {err}", window.inner);
+                warn!("Failed to move window {window:?}: {err}");
             }
         }
     }
 
-    fn ajust_scroll(&mut self, windows_positions: &[i32]) -> bool {
-        if let Some((index, focused_window)) = self
-            .windows
-            .iter()
-            .enumerate()
-            .find(|(_, window_item)| window_item.inner.is_focused().unwrap_or(false))
-        {
-            let focused_window_left = windows_positions[index] - self.padding - self.scroll_offset;
-            let focused_window_right =
-                focused_window_left + focused_window.width + self.padding * 2;
+    /// Returns the tiled window under the point, as laid out at the current scroll offset
+    pub fn window_at(&self, x: i32, y: i32) -> Option<WindowId> {
+        self.window_rects()
+            .into_iter()
+            .find(|(_, rect)| rect.contains(x, y))
+            .map(|(window, _)| window)
+    }
+
+    /// Returns the index of the column spanning `x` with the gap after it, or of the first or last
+    /// column when `x` is beyond the strip
+    pub fn column_index_at(&self, x: i32) -> Option<usize> {
+        let positions = self.windows_positions();
+        let last = self.columns.len().checked_sub(1)?;
+        Some(
+            self.columns
+                .iter()
+                .zip(positions)
+                .position(|(column, position)| {
+                    let right = self.area().x + position - self.scroll_offset
+                        + self.column_width(column)
+                        + self.inner_gap(column);
+                    x < right
+                })
+                .unwrap_or(last),
+        )
+    }
 
-            if focused_window_left >= 0 && focused_window_right <= self.screen_width {
-                return false;
+    fn window_rects_at(&self, windows_positions: &[i32]) -> Vec<(WindowId, Rectangle)> {
+        let mut rects = Vec::new();
+        for (column, x) in self.columns.iter().zip(windows_positions) {
+            let width = self.column_width(column);
+            let outer_gap = self.outer_gap(column);
+            let inner_gap = self.inner_gap(column);
+            #[allow(
+                clippy::cast_possible_truncation,
+                clippy::cast_possible_wrap,
+                reason = "a column will never hold enough windows to overflow an i32"
+            )]
+            let window_count = column.windows.len() as i32;
+            let available_height =
+                self.area().height - outer_gap * 2 - inner_gap * (window_count - 1);
+            let height = available_height / window_count;
+
+            let x = self.area().x + x - self.scroll_offset;
+            let mut y = self.area().y + outer_gap;
+            for (index, window) in column.windows.iter().enumerate() {
+                // The last window takes the pixels lost to the integer division
+                let height = if index == column.windows.len() - 1 {
+                    available_height - height * (window_count - 1)
+                } else {
+                    height
+                };
+                let rect = if column.is_expanded(ExpandMode::Fullscreen)
+                    && index == column.active.min(column.windows.len() - 1)
+                {
+                    self.screen
+                } else {
+                    Rectangle {
+                        x,
+                        y,
+                        width,
+                        height,
+                    }
+                };
+                rects.push((*window, rect));
+                y += height + inner_gap;
             }
+        }
+        rects
+    }
 
-            let window_left_to_screen_left = focused_window_left.abs();
-            let window_right_to_screen_right = focused_window_right.sub(self.screen_width).abs();
+    fn ajust_scroll(&mut self, backend: &impl Backend, windows_positions: &[i32]) -> bool {
+        // Changes of the strip itself may still move the hovered window off screen
+        if self.pointer_focus.is_some() && !self.layout_pending {
+            return false;
+        }
+        if let Some((index, _)) = self.focus_index(backend) {
+            let focused_column_width = self.column_width(&self.columns[index]);
+            let outer_gap = self.outer_gap(&self.columns[index]);
+            let screen_width = self.area().width;
+            let focused_window_left = windows_positions[index] - outer_gap - self.scroll_offset;
+            let focused_window_right = focused_window_left + focused_column_width + outer_gap * 2;
+            let fits_on_screen = focused_window_left >= 0 && focused_window_right <= screen_width;
 
-            if window_left_to_screen_left < window_right_to_screen_right {
-                self.scroll_offset -= window_left_to_screen_left;
-                window_left_to_screen_left != 0
-            } else {
-                self.scroll_offset += window_right_to_screen_right;
-                window_right_to_screen_right != 0
+            match self.scroll_policy {
+                ScrollPolicy::Minimal | ScrollPolicy::CenterIfNotFit if fits_on_screen => false,
+                ScrollPolicy::Minimal => {
+                    let window_left_to_screen_left = focused_window_left.abs();
+                    let window_right_to_screen_right = focused_window_right.sub(screen_width).abs();
+
+                    if window_left_to_screen_left < window_right_to_screen_right {
+                        self.scroll_to(self.scroll_offset - window_left_to_screen_left);
+                        window_left_to_screen_left != 0
+                    } else {
+                        self.scroll_to(self.scroll_offset + window_right_to_screen_right);
+                        window_right_to_screen_right != 0
+                    }
+                }
+                ScrollPolicy::Center | ScrollPolicy::CenterIfNotFit => {
+                    let centered_scroll_offset =
+                        windows_positions[index] + focused_column_width / 2 - screen_width / 2;
+                    let changed = centered_scroll_offset != self.scroll_offset;
+                    self.scroll_to(centered_scroll_offset);
+                    changed
+                }
             }
         } else {
             false
@@ -193,13 +1182,476 @@ impl ScrollTiler {
     pub fn windows_positions(&self) -> Vec<i32> {
         let mut positions = Vec::new();
         let mut current_position = 0;
+        let mut previous_column: Option<&Column> = None;
 
-        for window in &self.windows {
-            current_position += self.padding;
+        for column in &self.columns {
+            current_position += match previous_column {
+                None => self.outer_gap(column),
+                // Expanded columns touch their neighbours
+                Some(previous_column) => {
+                    self.inner_gap(previous_column).min(self.inner_gap(column))
+                }
+            };
             positions.push(current_position);
-            current_position += window.width + self.padding;
+            current_position += self.column_width(column);
+            previous_column = Some(column);
         }
 
         positions
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::fake::{FakeBackend, sync, tiler, tiler_on};
+
+    /// Opens windows one snapshot at a time so that their order in the strip is known
+    fn open(tiler: &mut ScrollTiler, backend: &FakeBackend, count: usize) -> Vec<WindowId> {
+        (0..count)
+            .map(|index| {
+                let window = backend.open(&format!("window {index}"));
+                sync(tiler, backend);
+                window
+            })
+            .collect()
+    }
+
+    fn rect(x: i32, y: i32, width: i32, height: i32) -> Rectangle {
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn new_windows_are_appended_as_columns() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+
+        let windows = open(&mut tiler, &backend, 2);
+
+        // The second column overflows by 40 pixels, so the strip scrolls by that much
+        assert_eq!(backend.rect(windows[0]), rect(-30, 10, 500, 480));
+        assert_eq!(backend.rect(windows[1]), rect(490, 10, 500, 480));
+    }
+
+    #[test]
+    fn closed_windows_are_removed_from_the_strip() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 3);
+
+        backend.close(windows[1]);
+        backend.focus(windows[2]).unwrap();
+        sync(&mut tiler, &backend);
+
+        assert_eq!(
+            tiler.windows().collect::<Vec<_>>(),
+            [windows[0], windows[2]]
+        );
+    }
+
+    #[test]
+    fn focus_moves_between_columns() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 3);
+
+        tiler.focus_left(&backend);
+        assert_eq!(backend.focused_window(), Some(windows[1]));
+        tiler.focus_left(&backend);
+        tiler.focus_left(&backend);
+        assert_eq!(backend.focused_window(), Some(windows[0]));
+        tiler.focus_right(&backend);
+        assert_eq!(backend.focused_window(), Some(windows[1]));
+    }
+
+    #[test]
+    fn swap_moves_the_focused_column() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 3);
+
+        tiler.swap_current_left(&backend);
+        sync(&mut tiler, &backend);
+
+        assert_eq!(
+            tiler.windows().collect::<Vec<_>>(),
+            [windows[0], windows[2], windows[1]]
+        );
+        assert_eq!(backend.rect(windows[2]).x, backend.rect(windows[0]).x + 520);
+    }
+
+    #[test]
+    fn consumed_windows_share_the_column_height() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 2);
+        tiler.take_events();
+
+        tiler.consume_or_expel_left(&backend);
+        sync(&mut tiler, &backend);
+
+        // The inner gap separates the windows, the outer gap the screen edges
+        assert_eq!(backend.rect(windows[0]), rect(10, 10, 500, 230));
+        assert_eq!(backend.rect(windows[1]), rect(10, 260, 500, 230));
+        assert_eq!(
+            tiler.take_events(),
+            [
+                StateEvent::ColumnsChanged {
+                    columns: vec![vec![windows[0], windows[1]]]
+                },
+                StateEvent::ScrollOffsetChanged { offset: 0 },
+            ]
+        );
+
+        tiler.swap_current_up(&backend);
+        assert_eq!(
+            tiler.take_events(),
+            [StateEvent::ColumnsChanged {
+                columns: vec![vec![windows[1], windows[0]]]
+            }]
+        );
+        assert_eq!(backend.focused_window(), Some(windows[1]));
+
+        tiler.focus_down(&backend);
+        tiler.consume_or_expel_right(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(
+            tiler.windows().collect::<Vec<_>>(),
+            [windows[1], windows[0]]
+        );
+        assert!(tiler.take_events().contains(&StateEvent::ColumnsChanged {
+            columns: vec![vec![windows[1]], vec![windows[0]]]
+        }));
+    }
+
+    #[test]
+    fn expelled_windows_are_laid_out_without_a_snapshot() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 2);
+        tiler.consume_or_expel_left(&backend);
+        tiler.set_width(&backend, 0.25);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[1]), rect(10, 260, 250, 230));
+
+        // Both columns fit on screen without scrolling, only the layout flag moves the windows
+        tiler.consume_or_expel_right(&backend);
+        tiler.refresh(&backend);
+        assert_eq!(backend.rect(windows[0]), rect(10, 10, 250, 480));
+        assert_eq!(backend.rect(windows[1]), rect(280, 10, 250, 480));
+    }
+
+    #[test]
+    fn the_overview_places_the_windows_until_it_closes() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 2);
+        tiler.set_in_overview(true);
+        backend
+            .move_window(windows[0], rect(0, 0, 100, 100))
+            .unwrap();
+
+        // Scrolling to the focused column and new snapshots leave the overview layout alone
+        tiler.focus_left(&backend);
+        tiler.refresh(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[0]), rect(0, 0, 100, 100));
+
+        tiler.set_in_overview(false);
+        tiler.refresh(&backend);
+        assert_eq!(backend.rect(windows[0]), rect(10, 10, 500, 480));
+    }
+
+    #[test]
+    fn minimal_scroll_brings_back_the_focused_column() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 3);
+
+        tiler.focus_left(&backend);
+        tiler.focus_left(&backend);
+        sync(&mut tiler, &backend);
+
+        assert_eq!(backend.rect(windows[0]).x, 10);
+    }
+
+    #[test]
+    fn center_scroll_keeps_the_focused_column_in_the_middle() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Center);
+        let windows = open(&mut tiler, &backend, 3);
+
+        assert_eq!(backend.rect(windows[2]).x, 250);
+
+        tiler.focus_left(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[1]).x, 250);
+    }
+
+    #[test]
+    fn cycle_width_goes_through_presets() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 1);
+
+        tiler.cycle_width(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[0]).width, 1000);
+
+        tiler.cycle_width(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[0]).width, 500);
+    }
+
+    #[test]
+    fn resized_columns_are_laid_out_without_a_snapshot() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 2);
+        tiler.focus_left(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[0]), rect(10, 10, 500, 480));
+
+        // The column stays in view without scrolling, only the layout flag moves the windows
+        tiler.set_width(&backend, 0.25);
+        tiler.refresh(&backend);
+        assert_eq!(backend.rect(windows[0]), rect(10, 10, 250, 480));
+        assert_eq!(backend.rect(windows[1]).x, 280);
+
+        tiler.cycle_width(&backend);
+        tiler.refresh(&backend);
+        assert_eq!(backend.rect(windows[0]), rect(10, 10, 500, 480));
+        assert_eq!(backend.rect(windows[1]).x, 530);
+    }
+
+    #[test]
+    fn new_windows_are_inserted_next_to_the_focused_column() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler_on(
+            &backend.monitors[0],
+            ScrollPolicy::Minimal,
+            InsertPosition::AfterFocused,
+        );
+        let windows = open(&mut tiler, &backend, 3);
+        assert_eq!(tiler.windows().collect::<Vec<_>>(), windows);
+
+        tiler.focus_left(&backend);
+        tiler.focus_left(&backend);
+        sync(&mut tiler, &backend);
+        let after = open(&mut tiler, &backend, 1)[0];
+        assert_eq!(
+            tiler.windows().collect::<Vec<_>>(),
+            [windows[0], after, windows[1], windows[2]]
+        );
+
+        let mut tiler = tiler_on(
+            &backend.monitors[0],
+            ScrollPolicy::Minimal,
+            InsertPosition::BeforeFocused,
+        );
+        sync(&mut tiler, &backend);
+        let before = open(&mut tiler, &backend, 1)[0];
+        let windows = tiler.windows().collect::<Vec<_>>();
+        assert_eq!(windows.iter().position(|window| *window == before), Some(0));
+    }
+
+    #[test]
+    fn windows_of_one_snapshot_are_inserted_in_z_order() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler_on(
+            &backend.monitors[0],
+            ScrollPolicy::Minimal,
+            InsertPosition::Start,
+        );
+        let first = open(&mut tiler, &backend, 1)[0];
+
+        let bottom = backend.open("bottom");
+        let top = backend.open("top");
+        sync(&mut tiler, &backend);
+
+        assert_eq!(tiler.windows().collect::<Vec<_>>(), [top, bottom, first]);
+    }
+
+    #[test]
+    fn floating_windows_leave_the_strip_with_their_geometry() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let first = open(&mut tiler, &backend, 1)[0];
+        let floating = backend.open("floating");
+        backend
+            .move_window(floating, rect(100, 50, 300, 200))
+            .unwrap();
+        sync(&mut tiler, &backend);
+
+        tiler.toggle_floating(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(floating), rect(100, 50, 300, 200));
+        // Minimal scrolling keeps the offset the strip had before
+        assert_eq!(backend.rect(first), rect(-30, 10, 500, 480));
+        assert_eq!(tiler.windows().collect::<Vec<_>>(), [first, floating]);
+
+        tiler.toggle_floating(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(floating), rect(490, 10, 500, 480));
+    }
+
+    #[test]
+    fn reserved_edges_are_left_free() {
+        let backend = FakeBackend::new();
+        let mut tiler = ScrollTiler::new(
+            Gaps {
+                inner: 10,
+                outer: 5,
+                reserved: Edges {
+                    top: 30,
+                    left: 100,
+                    ..Default::default()
+                },
+            },
+            vec![0.5, 1.0],
+            0.5,
+            ScrollPolicy::Minimal,
+            InsertPosition::End,
+            &FakeBackend::new().monitors[0],
+        );
+        let window = open(&mut tiler, &backend, 1)[0];
+        assert_eq!(backend.rect(window), rect(105, 35, 450, 460));
+
+        tiler.toggle_maximized(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(window), rect(100, 30, 900, 470));
+
+        // Fullscreen windows still cover the reserved edges
+        tiler.toggle_fullscreen(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(window), rect(0, 0, 1000, 500));
+
+        // Windows stacked in a column are the inner gap apart
+        tiler.toggle_fullscreen(&backend);
+        let stacked = open(&mut tiler, &backend, 1)[0];
+        tiler.consume_or_expel_left(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(window), rect(105, 35, 450, 225));
+        assert_eq!(backend.rect(stacked), rect(105, 270, 450, 225));
+    }
+
+    #[test]
+    fn gaps_scale_with_the_monitor_dpi() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let window = open(&mut tiler, &backend, 1)[0];
+
+        // Going from 100% to 200% scaling doubles the gaps, widths staying proportions
+        tiler.set_monitor(&Monitor {
+            dpi: 2 * DEFAULT_DPI,
+            ..backend.monitors[0]
+        });
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(window), rect(20, 20, 500, 460));
+    }
+
+    #[test]
+    fn expanded_columns_restore_their_width_and_scroll() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 2);
+
+        tiler.toggle_maximized(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[1]), rect(0, 0, 1000, 500));
+        // Expanded columns touch their neighbours
+        assert_eq!(backend.rect(windows[0]), rect(-500, 10, 500, 480));
+
+        // Going fullscreen from a maximized column still restores the original state
+        tiler.toggle_fullscreen(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[1]), rect(0, 0, 1000, 500));
+
+        tiler.toggle_fullscreen(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[0]), rect(-30, 10, 500, 480));
+        assert_eq!(backend.rect(windows[1]), rect(490, 10, 500, 480));
+    }
+
+    #[test]
+    fn changes_are_reported_as_events() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 2);
+        assert_eq!(
+            tiler.take_events(),
+            [
+                StateEvent::WindowAdded {
+                    window: windows[0],
+                    floating: false
+                },
+                StateEvent::FocusChanged { window: windows[0] },
+                StateEvent::WindowAdded {
+                    window: windows[1],
+                    floating: false
+                },
+                StateEvent::FocusChanged { window: windows[1] },
+                StateEvent::ScrollOffsetChanged { offset: 40 },
+            ]
+        );
+
+        tiler.swap_current_left(&backend);
+        tiler.cycle_width(&backend);
+        backend.close(windows[0]);
+        sync(&mut tiler, &backend);
+        assert_eq!(
+            tiler.take_events(),
+            [
+                StateEvent::ColumnMoved { from: 1, to: 0 },
+                StateEvent::WidthChanged {
+                    column: 0,
+                    width: 1.0
+                },
+                StateEvent::WindowRemoved { window: windows[0] },
+                StateEvent::ScrollOffsetChanged { offset: 60 },
+            ]
+        );
+    }
+
+    #[test]
+    fn dragged_columns_are_dropped_under_the_cursor() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 3);
+        // The strip is scrolled to show the last column from x = 0 to 1000
+        assert_eq!(tiler.window_at(100, 100), Some(windows[1]));
+        assert_eq!(tiler.column_index_at(100), Some(1));
+        assert_eq!(tiler.column_index_at(-5000), Some(0));
+        assert_eq!(tiler.column_index_at(5000), Some(2));
+
+        tiler.move_column(windows[2], 0);
+        sync(&mut tiler, &backend);
+        assert_eq!(
+            tiler.windows().collect::<Vec<_>>(),
+            [windows[2], windows[0], windows[1]]
+        );
+    }
+
+    #[test]
+    fn hovered_windows_are_focused_without_scrolling() {
+        let backend = FakeBackend::new();
+        let mut tiler = tiler(ScrollPolicy::Minimal);
+        let windows = open(&mut tiler, &backend, 3);
+        assert_eq!(backend.rect(windows[1]).x, -30);
+
+        tiler.focus_under_pointer(&backend, windows[1]);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.focused_window(), Some(windows[1]));
+        assert_eq!(backend.rect(windows[1]).x, -30);
+
+        // Keyboard focus scrolls again
+        tiler.focus_left(&backend);
+        sync(&mut tiler, &backend);
+        assert_eq!(backend.rect(windows[0]).x, 10);
+    }
+}
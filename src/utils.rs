@@ -10,6 +10,22 @@ macro_rules! function {
     }};
 }
 
+/// Offsets `index` by `direction`, clamping the result to a valid index of a collection of `len`
+/// elements, or 0 when it is empty
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    reason = "to add a potential negative number to a usize"
+)]
+pub fn offset_index(index: usize, direction: i32, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    (index as i32 + direction).clamp(0, len as i32 - 1) as usize
+}
+
+#[cfg(windows)]
 pub mod winapi {
     use windows::Win32::Foundation::{GetLastError, SetLastError, WIN32_ERROR};
 
@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use log::error;
 use windows::{
     Win32::{
@@ -9,24 +7,16 @@ use windows::{
     core::BOOL,
 };
 
-use crate::window::Window;
-
-const SYSTEM_CLASSES: &[&str] = &[
-    "Progman",
-    "TopLevelWindowForOverflowXamlIsland",
-    "XamlExplorerHostIslandWindow",
-    "Xaml_WindowedPopupClass",
-    "Shell_TrayWnd",
-];
+use crate::{
+    backend::{WindowId, cache::PropertyCache},
+    window::{Window, is_dialog},
+};
 
-const PROCESS_NAMES: &[&str] = &[
-    "Microsoft.CmdPal.UI.exe",
-    "PowerToys.MeasureToolUI.exe",
-    "ShareX.exe",
-    "SnippingTool.exe",
-    "PowerToys.PowerLauncher.exe",
-    "Ditto.exe",
-];
+/// Windows that are never tiled, on top of the ones that can not be
+pub struct WindowFilter {
+    pub ignored_classes: Vec<String>,
+    pub ignored_processes: Vec<String>,
+}
 
 macro_rules! filter_out_if {
     ($bool:expr) => {
@@ -36,36 +26,60 @@ macro_rules! filter_out_if {
     };
 }
 
-pub fn is_managed_window(window: Window) -> anyhow::Result<bool> {
+/// Visibility changes with every workspace switch and is always read, the other properties
+/// coming from `cache`
+pub fn is_managed_window(
+    window: Window,
+    filter: &WindowFilter,
+    cache: &PropertyCache,
+) -> anyhow::Result<bool> {
+    let id = WindowId::from(window);
     filter_out_if!(!window.is_visible()?);
     filter_out_if!(window.is_cloaked()?);
     filter_out_if!(!window.is_ancestor()?);
-    filter_out_if!(window.is_dialog()?);
-    filter_out_if!(window.title()?.is_none());
-    filter_out_if!(SYSTEM_CLASSES.contains(&window.class()?.as_str()));
-    filter_out_if!(PROCESS_NAMES.contains(&window.process_name()?.as_str()));
+    filter_out_if!(is_dialog(cache.style(id, || window.style())?));
+    filter_out_if!(cache.title(id, || window.title())?.is_none());
+    filter_out_if!(
+        filter
+            .ignored_classes
+            .contains(&cache.class(id, || window.class())?)
+    );
+    filter_out_if!(
+        filter
+            .ignored_processes
+            .contains(&cache.process_name(id, || window.process_name())?)
+    );
 
     Ok(true)
 }
 
-pub fn opened_windows() -> anyhow::Result<HashSet<Window>> {
+/// Returns the managed windows in z-order, `EnumWindows` enumerating them from the top
+pub fn opened_windows(filter: &WindowFilter, cache: &PropertyCache) -> anyhow::Result<Vec<Window>> {
     unsafe extern "system" fn enum_callback(window: HWND, out_list: LPARAM) -> BOOL {
         let list = unsafe { &mut *(out_list.0 as *mut Vec<HWND>) };
         list.push(window);
         true.into() // Continue enumeration
     }
 
-    let mut result = Vec::new();
+    let mut result: Vec<HWND> = Vec::new();
 
     unsafe {
         EnumWindows(Some(enum_callback), LPARAM(&raw mut result as isize))?;
     }
 
+    // Unmanaged windows are kept as well, their properties are what filters them out
+    cache.retain(
+        &result
+            .iter()
+            .map(|hwnd| WindowId(hwnd.0 as isize))
+            .collect(),
+    );
+
     let windows = result
         .into_iter()
         .filter_map(|hwnd| Window::from(hwnd).ok())
         .filter(|window| {
-            is_managed_window(*window)
+            is_managed_window(*window, filter, cache)
                 .inspect_err(|err| {
                     error!(
                         "Error filtering window ({err}): {}",
@@ -74,7 +88,7 @@ pub fn opened_windows() -> anyhow::Result<HashSet<Window>> {
                 })
                 .unwrap_or(false)
         })
-        .collect::<HashSet<_>>();
+        .collect::<Vec<_>>();
 
     Ok(windows)
 }
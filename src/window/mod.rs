@@ -17,14 +17,15 @@ use windows::{
         UI::WindowsAndMessaging::{
             GA_ROOT, GWL_STYLE, GetAncestor, GetClassNameW, GetClientRect, GetWindowLongW,
             GetWindowRect, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
-            IsWindow, IsWindowVisible, MoveWindow, SW_RESTORE, SetForegroundWindow, ShowWindow,
-            WINDOW_LONG_PTR_INDEX, WINDOW_STYLE, WS_DLGFRAME, WS_POPUP,
+            IsWindow, IsWindowVisible, MoveWindow, SW_HIDE, SW_RESTORE, SW_SHOWNA,
+            SetForegroundWindow, ShowWindow, WINDOW_LONG_PTR_INDEX, WINDOW_STYLE, WS_DLGFRAME,
+            WS_POPUP,
         },
     },
     core::BOOL,
 };
 
-use crate::{wincall_into_result, wincall_result};
+use crate::{backend::Rectangle, wincall_into_result, wincall_result};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Window {
@@ -37,14 +38,6 @@ impl Hash for Window {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Rectangle {
-    pub x: i32,
-    pub y: i32,
-    pub width: i32,
-    pub height: i32,
-}
-
 impl From<RECT> for Rectangle {
     fn from(rect: RECT) -> Self {
         Self {
@@ -112,11 +105,9 @@ impl Window {
         wincall_into_result!(GetWindowLongW(self.handle(), attribute))
     }
 
-    pub fn is_dialog(self) -> anyhow::Result<bool> {
+    pub fn style(self) -> anyhow::Result<u32> {
         ensure_valid!(self);
-        let style = self.get_window_long(GWL_STYLE)?;
-        let style = WINDOW_STYLE(style as u32);
-        Ok(style.contains(WS_POPUP) && style.contains(WS_DLGFRAME))
+        Ok(self.get_window_long(GWL_STYLE)? as u32)
     }
 
     pub fn title(self) -> anyhow::Result<Option<String>> {
@@ -237,6 +228,18 @@ impl Window {
         Ok(())
     }
 
+    pub fn hide(self) -> anyhow::Result<()> {
+        ensure_valid!(self);
+        let _ = wincall_into_result!(ShowWindow(self.handle(), SW_HIDE))?;
+        Ok(())
+    }
+
+    pub fn show(self) -> anyhow::Result<()> {
+        ensure_valid!(self);
+        let _ = wincall_into_result!(ShowWindow(self.handle(), SW_SHOWNA))?;
+        Ok(())
+    }
+
     pub fn client_rect(self) -> anyhow::Result<Rectangle> {
         ensure_valid!(self);
         let mut rect = RECT::default();
@@ -337,3 +340,9 @@ impl Window {
         res
     }
 }
+
+/// Returns whether a window with this style is a dialog box
+pub const fn is_dialog(style: u32) -> bool {
+    let style = WINDOW_STYLE(style);
+    style.contains(WS_POPUP) && style.contains(WS_DLGFRAME)
+}
@@ -0,0 +1,307 @@
+use log::{info, warn};
+
+use crate::{
+    backend::{Backend, WindowId},
+    event::StateEvent,
+    tiler::{Placement, ScrollTiler},
+};
+
+pub struct Workspace {
+    name: String,
+    tiler: ScrollTiler,
+}
+
+impl Workspace {
+    fn set_windows_visibility(&self, backend: &impl Backend, visible: bool) {
+        for window in self.tiler.windows() {
+            if let Err(err) = backend.set_visible(window, visible) {
+                warn!(
+                    "Failed to {} window {window:?} of workspace {}: {err}",
+                    if visible { "show" } else { "hide" },
+                    self.name
+                );
+            }
+        }
+    }
+}
+
+/// Set of scroll strips, only the active one being visible
+pub struct Workspaces {
+    workspaces: Vec<Workspace>,
+    active: usize,
+    /// Changes not yet collected by `take_events`
+    events: Vec<StateEvent>,
+}
+
+impl Workspaces {
+    /// Creates one workspace per name, each starting with a copy of `tiler`
+    pub fn new(names: &[&str], tiler: &ScrollTiler) -> Self {
+        Self {
+            workspaces: names
+                .iter()
+                .map(|name| Workspace {
+                    name: (*name).to_string(),
+                    tiler: tiler.clone(),
+                })
+                .collect(),
+            active: 0,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn active_tiler(&self) -> &ScrollTiler {
+        &self.workspaces[self.active].tiler
+    }
+
+    pub fn active_tiler_mut(&mut self) -> &mut ScrollTiler {
+        &mut self.workspaces[self.active].tiler
+    }
+
+    pub fn contains(&self, window: WindowId) -> bool {
+        self.owning_workspace(window).is_some()
+    }
+
+    fn owning_workspace(&self, window: WindowId) -> Option<usize> {
+        self.workspaces
+            .iter()
+            .position(|workspace| workspace.tiler.contains(window))
+    }
+
+    pub const fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub const fn len(&self) -> usize {
+        self.workspaces.len()
+    }
+
+    pub fn tilers(&self) -> impl Iterator<Item = &ScrollTiler> {
+        self.workspaces.iter().map(|workspace| &workspace.tiler)
+    }
+
+    pub fn tilers_mut(&mut self) -> impl Iterator<Item = &mut ScrollTiler> {
+        self.workspaces
+            .iter_mut()
+            .map(|workspace| &mut workspace.tiler)
+    }
+
+    pub fn tiler_mut(&mut self, index: usize) -> Option<&mut ScrollTiler> {
+        self.workspaces
+            .get_mut(index)
+            .map(|workspace| &mut workspace.tiler)
+    }
+
+    /// Returns the tiler of the workspace holding `window`
+    pub fn tiler_of_mut(&mut self, window: WindowId) -> Option<&mut ScrollTiler> {
+        let index = self.owning_workspace(window)?;
+        Some(&mut self.workspaces[index].tiler)
+    }
+
+    /// Adds a new window to the workspace at `index`, it is hidden by the next snapshot if that
+    /// workspace is inactive
+    pub fn place_window(&mut self, index: usize, window: WindowId, placement: &Placement) {
+        self.workspaces[index].tiler.place_window(window, placement);
+    }
+
+    /// Drops a destroyed window, laying the active strip out again when it held it
+    pub fn remove_window(&mut self, backend: &impl Backend, window: WindowId) {
+        if let Some(index) = self.owning_workspace(window) {
+            let tiler = &mut self.workspaces[index].tiler;
+            tiler.remove_window(window);
+            if index == self.active {
+                tiler.refresh(backend);
+            }
+        }
+    }
+
+    /// Returns the changes of the workspaces and of their strips since the last call
+    pub fn take_events(&mut self) -> Vec<StateEvent> {
+        let mut events = std::mem::take(&mut self.events);
+        for workspace in &mut self.workspaces {
+            events.extend(workspace.tiler.take_events());
+        }
+        events
+    }
+
+    pub fn handle_window_snapshot(
+        &mut self,
+        backend: &impl Backend,
+        windows_snapshot: &[WindowId],
+        placement: &impl Fn(WindowId) -> Placement,
+    ) {
+        let mut active_windows = Vec::with_capacity(windows_snapshot.len());
+
+        for window in windows_snapshot {
+            match self.owning_workspace(*window) {
+                Some(index) if index != self.active => {
+                    // The window showed itself while its workspace is inactive
+                    if let Err(err) = backend.set_visible(*window, false) {
+                        warn!("Failed to hide window {window:?}: {err}");
+                    }
+                }
+                _ => {
+                    active_windows.push(*window);
+                }
+            }
+        }
+
+        for (index, workspace) in self.workspaces.iter_mut().enumerate() {
+            if index != self.active {
+                workspace
+                    .tiler
+                    .retain_windows(|window| backend.is_valid(window));
+            }
+        }
+
+        self.active_tiler_mut()
+            .handle_window_snapshot(backend, &active_windows, placement);
+    }
+
+    pub fn switch_to(&mut self, backend: &impl Backend, index: usize) {
+        if index == self.active || index >= self.workspaces.len() {
+            return;
+        }
+
+        self.workspaces[self.active].set_windows_visibility(backend, false);
+        self.workspaces[self.active].tiler.clear_focus();
+        self.active = index;
+        let workspace = &self.workspaces[self.active];
+        info!("Switched to workspace {}", workspace.name);
+        self.events.push(StateEvent::WorkspaceChanged {
+            workspace: workspace.name.clone(),
+        });
+        workspace.set_windows_visibility(backend, true);
+        workspace.tiler.focus_active(backend);
+    }
+
+    /// Moves the focused window to the workspace at `index`, switching to it if `follow` is set
+    pub fn move_focused_window_to(&mut self, backend: &impl Backend, index: usize, follow: bool) {
+        if index == self.active || index >= self.workspaces.len() {
+            return;
+        }
+
+        let Some((window, layer)) = self.active_tiler_mut().take_focused_window(backend) else {
+            return;
+        };
+        self.workspaces[index].tiler.insert_window(window, layer);
+
+        if follow {
+            self.switch_to(backend, index);
+        } else {
+            if let Err(err) = backend.set_visible(window, false) {
+                warn!("Failed to hide window {window:?}: {err}");
+            }
+            self.workspaces[self.active].tiler.focus_active(backend);
+        }
+    }
+
+    /// Takes the windows of every workspace of `other` into the workspace of the same index,
+    /// showing the ones landing in the active workspace and hiding the others
+    pub fn append(&mut self, backend: &impl Backend, other: Self) {
+        for (index, workspace) in other.workspaces.into_iter().enumerate() {
+            let Some(target) = self.workspaces.get_mut(index) else {
+                continue;
+            };
+            let visible = index == self.active;
+            for window in workspace.tiler.windows() {
+                if let Err(err) = backend.set_visible(window, visible) {
+                    warn!("Failed to show or hide window {window:?}: {err}");
+                }
+            }
+            target.tiler.append(workspace.tiler);
+        }
+    }
+
+    /// Makes every window visible again and brings the ones scrolled off screen back inside of
+    /// it, so that none stay out of reach once winri exits
+    pub fn show_all(&self, backend: &impl Backend) {
+        for workspace in &self.workspaces {
+            workspace.set_windows_visibility(backend, true);
+            workspace.tiler.bring_into_view(backend);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::fake::{FakeBackend, sync, tiler},
+        tiler::ScrollPolicy,
+    };
+
+    fn workspaces() -> Workspaces {
+        Workspaces::new(&["1", "2"], &tiler(ScrollPolicy::Minimal))
+    }
+
+    #[test]
+    fn switching_hides_the_previous_workspace() {
+        let backend = FakeBackend::new();
+        let mut workspaces = workspaces();
+        let first = backend.open("first");
+        sync(&mut workspaces, &backend);
+
+        workspaces.switch_to(&backend, 1);
+        sync(&mut workspaces, &backend);
+        let second = backend.open("second");
+        sync(&mut workspaces, &backend);
+
+        assert!(!backend.is_visible(first));
+        assert!(workspaces.contains(first));
+        assert_eq!(
+            workspaces.active_tiler().windows().collect::<Vec<_>>(),
+            [second]
+        );
+
+        workspaces.switch_to(&backend, 0);
+        sync(&mut workspaces, &backend);
+        assert!(backend.is_visible(first));
+        assert!(!backend.is_visible(second));
+        assert_eq!(backend.focused_window(), Some(first));
+    }
+
+    #[test]
+    fn moved_window_follows_to_its_workspace() {
+        let backend = FakeBackend::new();
+        let mut workspaces = workspaces();
+        let first = backend.open("first");
+        sync(&mut workspaces, &backend);
+        let second = backend.open("second");
+        sync(&mut workspaces, &backend);
+
+        workspaces.move_focused_window_to(&backend, 1, false);
+        sync(&mut workspaces, &backend);
+        assert!(!backend.is_visible(second));
+        assert_eq!(backend.focused_window(), Some(first));
+
+        workspaces.switch_to(&backend, 1);
+        sync(&mut workspaces, &backend);
+        workspaces.move_focused_window_to(&backend, 0, true);
+        sync(&mut workspaces, &backend);
+        assert_eq!(
+            workspaces.active_tiler().windows().collect::<Vec<_>>(),
+            [first, second]
+        );
+        assert_eq!(backend.focused_window(), Some(second));
+    }
+
+    #[test]
+    fn every_window_is_within_reach_once_shown_for_exit() {
+        let backend = FakeBackend::new();
+        let mut workspaces = workspaces();
+        let windows = ["first", "second", "third"].map(|name| {
+            let window = backend.open(name);
+            sync(&mut workspaces, &backend);
+            window
+        });
+        assert!(backend.rect(windows[0]).x < 0);
+        workspaces.switch_to(&backend, 1);
+
+        workspaces.show_all(&backend);
+        for window in windows {
+            let rect = backend.rect(window);
+            assert!(backend.is_visible(window));
+            assert!(rect.x >= 0 && rect.x + rect.width <= 1000, "{rect:?}");
+        }
+    }
+}